@@ -0,0 +1,24 @@
+//! Bridges telemetry to a local MQTT broker (e.g. mosquitto on 127.0.0.1:1883).
+//!
+//! Usage: `cargo run --features mqtt --example mqtt_bridge -- [broker-host] [broker-port]`
+//!
+//! Watch with `mosquitto_sub -v -t 'thebus/#'`, send a command with
+//! `mosquitto_pub -t thebus/cmd -m 'sendeventpress?event=Horn'`.
+
+use the_bus_telemetry::api::RequestConfig;
+use the_bus_telemetry::mqtt::{MqttBridgeConfig, run_mqtt_bridge};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    let mut bridge = MqttBridgeConfig::new();
+    if let Some(host) = args.next() {
+        bridge = bridge.broker_host(host);
+    }
+    if let Some(port) = args.next() {
+        bridge = bridge.broker_port(port.parse()?);
+    }
+
+    let config = RequestConfig::new();
+    run_mqtt_bridge(&config, &bridge).await
+}
@@ -0,0 +1,25 @@
+//! Broadcasts binary telemetry packets on the local network.
+//!
+//! Usage: `cargo run --features udp --example udp_broadcast -- [target] [interval-ms]`
+//!
+//! The target defaults to "255.255.255.255:37339", the interval to 20ms (50 packets per second).
+//! Decode received packets with `the_bus_telemetry::udp::TelemetryPacket::decode`.
+
+use std::time::Duration;
+use the_bus_telemetry::api::RequestConfig;
+use the_bus_telemetry::udp::run_udp_broadcast;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    let target = args
+        .next()
+        .unwrap_or_else(|| "255.255.255.255:37339".to_string());
+    let interval = match args.next() {
+        Some(ms) => Duration::from_millis(ms.parse()?),
+        None => Duration::from_millis(20),
+    };
+
+    let config = RequestConfig::new();
+    run_udp_broadcast(&config, target.as_str(), interval).await
+}
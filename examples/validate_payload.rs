@@ -0,0 +1,36 @@
+//! Reports schema drift of saved telemetry payloads.
+//!
+//! Usage: `cargo run --example validate_payload -- vehicle1.json vehicle2.json ...`
+
+use std::fs;
+use std::process::ExitCode;
+use the_bus_telemetry::validation::validate_vehicle_payload;
+
+fn main() -> ExitCode {
+    let mut clean = true;
+
+    for path in std::env::args().skip(1) {
+        let data: serde_json::Value = match fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|s| serde_json::from_str(&s).map_err(|e| e.to_string()))
+        {
+            Ok(data) => data,
+            Err(e) => {
+                eprintln!("{}: {}", path, e);
+                clean = false;
+                continue;
+            }
+        };
+
+        let report = validate_vehicle_payload(&data);
+        clean &= report.is_clean();
+        println!("{}", path);
+        print!("{}", report);
+    }
+
+    if clean {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
@@ -0,0 +1,21 @@
+//! Serves telemetry to browser dashboards over WebSocket.
+//!
+//! Usage: `cargo run --features websocket --example websocket_server -- [listen-address]`
+//!
+//! Connect with `new WebSocket("ws://127.0.0.1:37338")` and send
+//! `{"type":"cmd","cmd":"sendeventpress?event=Horn"}` to press a button.
+
+use std::time::Duration;
+use the_bus_telemetry::api::RequestConfig;
+use the_bus_telemetry::websocket::run_websocket_server;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let listen = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:37338".to_string());
+
+    let config = RequestConfig::new();
+    println!("serving websocket on ws://{}", listen);
+    run_websocket_server(&config, &listen, Duration::from_millis(250)).await
+}
@@ -0,0 +1,199 @@
+//! This module reads kneeling, ramp and wheelchair request state and sends the matching commands.
+//!
+//! Button names vary between models ("Automatic Kneeling", "AutomaticKneeling", "Auto Kneeling"),
+//! so buttons are matched by their canonical name. Wheelchair requests are read from the
+//! "Wheelchair Request" button and from lamps like `LightDoorWheelchairRequestButtonMiddle`
+//! (request at the middle door) and `LED_DashboardInfoStopRequestWheelChair` (dashboard).
+
+use crate::api::{
+    ApiButton, ApiVehicleType, RequestConfig, canonical_lamp_name, send_telemetry_bus_event,
+};
+use serde::Serialize;
+
+const KNEELING_BUTTONS: &[&str] = &["Kneeling"];
+const AUTOMATIC_KNEELING_BUTTONS: &[&str] =
+    &["Automatic Kneeling", "AutomaticKneeling", "Auto Kneeling"];
+const DOOR_CLEARANCE_BUTTONS: &[&str] = &["Door Clearance", "DoorClearance"];
+const RAMP_BUTTONS: &[&str] = &["WheelchairRamp"];
+const WHEELCHAIR_REQUEST_BUTTONS: &[&str] = &["Wheelchair Request"];
+
+/// Prefix of the per-door wheelchair request lamps, followed by the door (e.g. "Middle").
+const DOOR_REQUEST_LAMP: &str = "lightdoorwheelchairrequestbutton";
+
+/// Accessibility state of the vehicle. Fields are `None` if the vehicle lacks the button.
+#[derive(Serialize, Debug, PartialEq, Default, Clone)]
+pub struct AccessibilityState {
+    /// True while the bus is kneeling (lowered).
+    pub kneeling: Option<bool>,
+    pub automatic_kneeling: Option<bool>,
+    pub door_clearance: Option<bool>,
+    /// True while the wheelchair ramp is extended.
+    pub ramp_out: Option<bool>,
+    /// True if a wheelchair stop is requested anywhere in the bus.
+    pub wheelchair_request: bool,
+    /// Doors with a lit wheelchair request button (e.g. "Middle").
+    pub wheelchair_request_doors: Vec<String>,
+}
+
+impl AccessibilityState {
+    /// Reads the accessibility state from the telemetry.
+    pub fn from_vehicle(v: &ApiVehicleType) -> Self {
+        let switch = |names: &[&str]| find_button(v, names).and_then(|b| b.switch_state());
+        let lamps = v.all_lamps.all();
+
+        let wheelchair_request_doors: Vec<String> = lamps
+            .iter()
+            .filter(|(_, value)| **value > 0.0)
+            .filter_map(|(name, _)| {
+                // Keep the case of the door name, e.g. "Middle".
+                let name: String = name.chars().filter(char::is_ascii_alphanumeric).collect();
+                let door = name.get(DOOR_REQUEST_LAMP.len()..)?;
+                (canonical_lamp_name(&name).starts_with(DOOR_REQUEST_LAMP) && !door.is_empty())
+                    .then(|| door.to_string())
+            })
+            .collect();
+        let dashboard_request = lamps.iter().any(|(name, value)| {
+            *value > 0.0 && canonical_lamp_name(name).contains("stoprequestwheelchair")
+        });
+
+        Self {
+            kneeling: switch(KNEELING_BUTTONS)
+                .or_else(|| v.all_lamps.get("LED Kneeling").map(|l| l > 0.0)),
+            automatic_kneeling: switch(AUTOMATIC_KNEELING_BUTTONS),
+            door_clearance: switch(DOOR_CLEARANCE_BUTTONS),
+            ramp_out: switch(RAMP_BUTTONS),
+            wheelchair_request: dashboard_request
+                || !wheelchair_request_doors.is_empty()
+                || switch(WHEELCHAIR_REQUEST_BUTTONS) == Some(true),
+            wheelchair_request_doors,
+        }
+    }
+}
+
+/// Returns the first button named like one of `names`, ignoring case.
+fn find_button(v: &ApiVehicleType, names: &[&str]) -> Option<ApiButton> {
+    v.buttons
+        .iter()
+        .find(|b| names.iter().any(|n| b.name.eq_ignore_ascii_case(n)))
+        .cloned()
+}
+
+/// Returns `action` if the button matching `names` offers it.
+fn button_action(v: &ApiVehicleType, names: &[&str], action: &str) -> Option<String> {
+    find_button(v, names)?
+        .actions
+        .iter()
+        .find(|a| a.eq_ignore_ascii_case(action))
+        .cloned()
+}
+
+/// Returns the event lowering (`down`) or raising the bus.
+pub fn kneel_event(v: &ApiVehicleType, down: bool) -> Option<String> {
+    let action = if down { "KneelDown" } else { "KneelUp" };
+    button_action(v, KNEELING_BUTTONS, action)
+}
+
+/// Returns the event extending (`out`) or retracting the wheelchair ramp.
+pub fn ramp_event(v: &ApiVehicleType, out: bool) -> Option<String> {
+    let action = if out {
+        "ElectricRampOut"
+    } else {
+        "ElectricRampIn"
+    };
+    button_action(v, RAMP_BUTTONS, action)
+}
+
+/// Returns the event toggling automatic kneeling.
+pub fn automatic_kneeling_event(v: &ApiVehicleType) -> Option<String> {
+    find_button(v, AUTOMATIC_KNEELING_BUTTONS)?
+        .action()
+        .map(str::to_string)
+}
+
+/// Returns the event toggling the door clearance.
+pub fn door_clearance_event(v: &ApiVehicleType) -> Option<String> {
+    find_button(v, DOOR_CLEARANCE_BUTTONS)?
+        .action()
+        .map(str::to_string)
+}
+
+/// Returns the event of the "Wheelchair Request" button.
+pub fn wheelchair_request_event(v: &ApiVehicleType) -> Option<String> {
+    find_button(v, WHEELCHAIR_REQUEST_BUTTONS)?
+        .action()
+        .map(str::to_string)
+}
+
+/// Sends an event or fails with "`what` is not available on this vehicle".
+async fn send_event(
+    config: &RequestConfig,
+    event: Option<String>,
+    what: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let event = event.ok_or_else(|| format!("{} is not available on this vehicle", what))?;
+    send_telemetry_bus_event(config, &event).await
+}
+
+/// Lowers (`down`) or raises the bus.
+pub async fn kneel(
+    config: &RequestConfig,
+    v: &ApiVehicleType,
+    down: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    send_event(config, kneel_event(v, down), "kneeling").await
+}
+
+/// Extends (`out`) or retracts the wheelchair ramp.
+pub async fn set_ramp(
+    config: &RequestConfig,
+    v: &ApiVehicleType,
+    out: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    send_event(config, ramp_event(v, out), "the wheelchair ramp").await
+}
+
+/// Toggles automatic kneeling.
+pub async fn toggle_automatic_kneeling(
+    config: &RequestConfig,
+    v: &ApiVehicleType,
+) -> Result<(), Box<dyn std::error::Error>> {
+    send_event(config, automatic_kneeling_event(v), "automatic kneeling").await
+}
+
+/// Toggles the door clearance.
+pub async fn toggle_door_clearance(
+    config: &RequestConfig,
+    v: &ApiVehicleType,
+) -> Result<(), Box<dyn std::error::Error>> {
+    send_event(config, door_clearance_event(v), "door clearance").await
+}
+
+/// A new wheelchair stop request.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct WheelchairStopRequest {
+    /// Doors the request was made at; empty if the door is not known.
+    pub doors: Vec<String>,
+}
+
+/// Detects new wheelchair stop requests, e.g. for a passenger information display.
+#[derive(Debug, Default)]
+pub struct WheelchairRequestDetector {
+    requested: bool,
+}
+
+impl WheelchairRequestDetector {
+    /// Creates a new detector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a snapshot and returns the request if a wheelchair stop was just requested.
+    pub fn update(&mut self, v: &ApiVehicleType) -> Option<WheelchairStopRequest> {
+        let state = AccessibilityState::from_vehicle(v);
+        let started = state.wheelchair_request && !self.requested;
+        self.requested = state.wheelchair_request;
+        started.then_some(WheelchairStopRequest {
+            doors: state.wheelchair_request_doors,
+        })
+    }
+}
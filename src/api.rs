@@ -1,6 +1,8 @@
 //! This module handles the raw interaction with The Bus Telemetry API.
 
-use serde::Deserialize;
+use crate::profile::MappingProfile;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::string::ToString;
 use std::time::Duration;
 
@@ -20,6 +22,12 @@ pub struct RequestConfig {
     pub debugging: bool,
 }
 
+impl Default for RequestConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl RequestConfig {
     /// Creates a new `RequestConfig` with default values.
     pub fn new() -> Self {
@@ -67,7 +75,7 @@ impl RequestConfig {
 }
 
 /// World telemetry data.
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Serialize, Debug, PartialEq, Default, Clone)]
 pub struct ApiWorldType {
     /// Name of the current level.
     #[serde(rename = "LevelName")]
@@ -84,10 +92,34 @@ pub struct ApiWorldType {
     /// Longitude of the world origin.
     #[serde(rename = "BaseLongitude")]
     pub base_longitude: f64,
+    /// Outside temperature in degrees Celsius.
+    #[serde(rename = "Temperature", default)]
+    pub temperature: f32,
+    /// Wetness of the roads (0.0 to 1.0).
+    #[serde(rename = "Wetness", default)]
+    pub wetness: f32,
+    /// Rain intensity (0.0 to 1.0).
+    #[serde(rename = "RainIntensity", default)]
+    pub rain_intensity: f32,
+    /// Snow intensity (0.0 to 1.0).
+    #[serde(rename = "SnowIntensity", default)]
+    pub snow_intensity: f32,
+    /// Snow cover on the ground (0.0 to 1.0).
+    #[serde(rename = "SnowPack", default)]
+    pub snow_pack: f32,
+    /// Cloud intensity (0.0 to 1.0).
+    #[serde(rename = "CloudIntensity", default)]
+    pub cloud_intensity: f32,
+    /// Fog intensity (0.0 to 1.0).
+    #[serde(rename = "NebularIntensity", default)]
+    pub nebular_intensity: f32,
+    /// Wind intensity (0.0 to 1.0).
+    #[serde(rename = "WindIntensity", default)]
+    pub wind_intensity: f32,
 }
 
 /// Vehicle telemetry data.
-#[derive(Deserialize, Debug, PartialEq, Default)]
+#[derive(Deserialize, Serialize, Debug, PartialEq, Default, Clone)]
 pub struct ApiVehicleType {
     /// Internal actor name.
     #[serde(rename = "ActorName")]
@@ -104,6 +136,30 @@ pub struct ApiVehicleType {
     /// Whether warning lights are active (string "true"/"false").
     #[serde(rename = "WarningLights")]
     pub warning_lights: String,
+    /// Whether the daytime running lights are on (string "true"/"false").
+    #[serde(rename = "DaytimeLight", default)]
+    pub daytime_light: String,
+    /// Whether the front fog light is on (string "true"/"false").
+    #[serde(rename = "FogLight", default)]
+    pub fog_light: String,
+    /// Whether the rear fog light is on (string "true"/"false").
+    #[serde(rename = "RearFogLight", default)]
+    pub rear_fog_light: String,
+    /// Whether the main light is on (string "true"/"false").
+    #[serde(rename = "MainLight", default)]
+    pub main_light: String,
+    /// Whether the dashboard light is on (string "true"/"false").
+    #[serde(rename = "DashboardLight", default)]
+    pub dashboard_light: String,
+    /// Whether the parking light is on (string "true"/"false").
+    #[serde(rename = "ParkingLight", default)]
+    pub parking_light: String,
+    /// Whether the headlight is on (string "true"/"false").
+    #[serde(rename = "Headlight", default)]
+    pub headlight: String,
+    /// Whether the high beam is on (string "true"/"false").
+    #[serde(rename = "TravellerLight", default)]
+    pub traveller_light: String,
     /// Whether any passenger door is open (string "true"/"false").
     #[serde(rename = "PassengerDoorsOpen")]
     pub passenger_doors_open: String,
@@ -113,25 +169,100 @@ pub struct ApiVehicleType {
     /// Current speed in km/h.
     #[serde(rename = "Speed")]
     pub speed: f32,
+    /// Engine revolutions per minute.
+    #[serde(rename = "RPM", default)]
+    pub rpm: f32,
+    /// Steering input (-1: left, 1: right).
+    #[serde(rename = "Steering", default)]
+    pub steering: f32,
+    /// Throttle input (0 to 1).
+    #[serde(rename = "Throttle", default)]
+    pub throttle: f32,
+    /// Brake input (0 to 1).
+    #[serde(rename = "Brake", default)]
+    pub brake: f32,
     /// Allowed speed limit.
     #[serde(rename = "AllowedSpeed")]
     pub allowed_speed: f32,
+    /// Whether the cruise control is active (string "true"/"false").
+    #[serde(rename = "CruiseControlActive", default)]
+    pub cruise_control_active: String,
+    /// Whether the destination display is switched on (string "true"/"false").
+    #[serde(rename = "DestinationDisplayActive", default)]
+    pub destination_display_active: String,
     /// Fuel level on display (0.0 to 1.0).
     #[serde(rename = "DisplayFuel")]
     pub display_fuel: f32,
+    /// Current fuel (litres) or battery charge.
+    #[serde(rename = "CurrentFuel", default)]
+    pub current_fuel: f32,
+    /// Fuel tank or battery capacity.
+    #[serde(rename = "MaxFuel", default)]
+    pub max_fuel: f32,
+    /// Whether the low fuel warning is shown (string "true"/"false").
+    #[serde(rename = "LowFuelWarning", default)]
+    pub low_fuel_warning: String,
+    /// Number of passenger seats.
+    #[serde(rename = "NumSeats", default)]
+    pub num_seats: u32,
+    /// Number of occupied passenger seats.
+    #[serde(rename = "NumOccupiedSeats", default)]
+    pub num_occupied_seats: u32,
+    /// Whether the bus is at a stop (string "true"/"false").
+    #[serde(rename = "IsAtStop", default)]
+    pub is_at_stop: String,
+    /// Whether the radio is playing (string "true"/"false").
+    #[serde(rename = "IsRadioPlaying", default)]
+    pub is_radio_playing: String,
+    /// Name of the active radio channel.
+    #[serde(rename = "ActiveRadioChannel", default)]
+    pub active_radio_channel: String,
+    /// Radio volume.
+    #[serde(rename = "RadioVolume", default)]
+    pub radio_volume: f32,
+    /// Wiper level as reported by the vehicle (0: off).
+    #[serde(rename = "WiperLevel", default)]
+    pub wiper_level: i32,
     /// Indicator state (-1: left, 0: off, 1: right).
     #[serde(rename = "IndicatorState")]
     pub indicator_state: i8,
+    /// Indicator value as reported by the vehicle.
+    #[serde(rename = "IndicatorValue", default)]
+    pub indicator_value: f32,
     /// Status of all external and internal lamps.
     #[serde(rename = "AllLamps")]
     pub all_lamps: ApiLamps,
     /// List of buttons and their states.
     #[serde(rename = "Buttons", default)]
     pub buttons: Vec<ApiButton>,
+    /// List of passenger doors and their states.
+    #[serde(rename = "Doors", default)]
+    pub doors: Vec<ApiDoor>,
+    /// Wheels of the vehicle, front to back.
+    #[serde(rename = "Wheels", default)]
+    pub wheels: Vec<ApiWheel>,
+    /// Gearbox type (e.g. "TorqueConverter", "None").
+    #[serde(rename = "GearboxType", default)]
+    pub gearbox_type: String,
+    /// Gearbox state.
+    #[serde(rename = "Gearbox", default)]
+    pub gearbox: ApiGearbox,
+    /// Position of the vehicle in the world.
+    #[serde(rename = "Location", default)]
+    pub location: ApiLocation,
+    /// Orientation of the vehicle in degrees.
+    #[serde(rename = "Rotation", default)]
+    pub rotation: ApiRotation,
+    /// Game logic state such as ticket sales.
+    #[serde(rename = "BusLogic", default)]
+    pub bus_logic: ApiBusLogic,
+    /// Paths of the UMG widget endpoints by name (e.g. "Atron" for the board computer).
+    #[serde(rename = "UMG", default)]
+    pub umg: BTreeMap<String, String>,
 }
 
 /// Represents various lamp intensities or states.
-#[derive(Deserialize, Debug, PartialEq, Default)]
+#[derive(Deserialize, Serialize, Debug, PartialEq, Default, Clone)]
 pub struct ApiLamps {
     #[serde(
         rename = "LightHeadlight",
@@ -193,10 +324,147 @@ pub struct ApiLamps {
         default
     )] // we need default man_lionscity does not have this field
     pub door_clearance_light: f32,
+    /// All other lamps of the payload by their original name.
+    /// Entries whose value is not a number are skipped.
+    #[serde(flatten, deserialize_with = "deserialize_numeric_lamps")]
+    pub other: BTreeMap<String, f32>,
+}
+
+/// Deserializes the untyped lamps, skipping entries whose value is not a number.
+fn deserialize_numeric_lamps<'de, D>(deserializer: D) -> Result<BTreeMap<String, f32>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let lamps = BTreeMap::<String, serde_json::Value>::deserialize(deserializer)?;
+    Ok(lamps
+        .into_iter()
+        .filter_map(|(name, value)| value.as_f64().map(|v| (name, v as f32)))
+        .collect())
+}
+
+/// Groups of normalized lamp names that denote the same lamp across vehicle models.
+/// The first entry of a group is its canonical name.
+///
+/// Every typed lamp of `ApiLamps` and every entry of `profile::BUILTIN_LAMPS` needs a group
+/// here; `tests/api_test.rs` checks that the tables agree.
+const LAMP_VARIANTS: &[&[&str]] = &[
+    &["lightheadlight", "lightheadlight1"],
+    &["lightparking", "lightparking1"],
+    &["lightmain"],
+    &["lighttraveling", "lighttraveling1"],
+    &["lightdaytime", "lightdaytime1"],
+    &["lightfog", "lightfog1", "lightfrontfog"],
+    &["lightrearfog", "lightrearfog1"],
+    &["lightreversal", "lightreverse"],
+    &["lightbrake"],
+    &["indicatorleft", "lightindicatorleft"],
+    &["indicatorright", "lightindicatorright"],
+    &["lightdriver", "driverlight"],
+    &[
+        "interiorlights",
+        "passengerlights",
+        "lightinteriorlowerdeck",
+    ],
+    &["doorbutton1", "buttonlightdoor1"],
+    &["doorbutton2", "buttonlightdoor2", "lightdoormiddle"],
+    &["doorbutton3", "buttonlightdoor3"],
+    &["doorbutton4", "buttonlightdoor4"],
+    &["ledstoprequest", "dbstoprequest", "tachostoprequest"],
+    &["buttonlightbusstopbrake", "ledstopbrake", "ledbusstopbrake"],
+    &["buttonlightdoorclearance", "doorclearancebutton"],
+    &[
+        "ledfixingbrake",
+        "ledparkingbrake",
+        "dbparkbrake",
+        "dashboardfixingbrake",
+        "tachofixingbrake",
+    ],
+    &["ledhighbeam", "tacholighttraveling"],
+    &["ledindicatorleft", "tachoindicatorleft"],
+    &[
+        "ledindicatorright",
+        "tachoindicatorright",
+        "dashboardindicatorright",
+    ],
+    &[
+        "ledwarning",
+        "dbwarninglight",
+        "buttonlightwarning",
+        "buttonlightwarninglights",
+        "warninglight",
+    ],
+];
+
+/// Returns the canonical names of all lamps known across vehicle models.
+pub fn known_lamp_names() -> impl Iterator<Item = &'static str> {
+    LAMP_VARIANTS.iter().map(|group| group[0])
+}
+
+/// Returns the canonical form of a lamp name, so that naming variants like
+/// "LightParking1", "Light Parking" and "LightParking" compare equal.
+pub fn canonical_lamp_name(name: &str) -> String {
+    let normalized: String = name
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    let normalized = normalized.replace("travelling", "traveling");
+
+    LAMP_VARIANTS
+        .iter()
+        .find(|group| group.contains(&normalized.as_str()))
+        .map(|group| group[0].to_string())
+        .unwrap_or(normalized)
+}
+
+impl ApiLamps {
+    /// Returns the typed lamps with their canonical payload names.
+    fn typed(&self) -> [(&'static str, f32); 11] {
+        [
+            ("LightHeadlight", self.light_headlight),
+            ("Light Parking", self.light_parking),
+            ("Light MAIN", self.light_main),
+            ("LightTraveling", self.traveller_light),
+            ("Door Button 1", self.front_door_light),
+            ("Door Button 2", self.second_door_light),
+            ("Door Button 3", self.third_door_light),
+            ("Door Button 4", self.fourth_door_light),
+            ("LED StopRequest", self.led_stop_request),
+            ("ButtonLight BusStopBrake", self.light_stopbrake),
+            ("ButtonLight DoorClearance", self.door_clearance_light),
+        ]
+    }
+
+    /// Returns the intensity of a lamp by name, accepting naming variants of other models
+    /// (e.g. "Light Reverse" finds "LightReversal"). Typed lamps take precedence.
+    pub fn get(&self, name: &str) -> Option<f32> {
+        let key = canonical_lamp_name(name);
+
+        self.typed()
+            .into_iter()
+            .find(|(n, _)| canonical_lamp_name(n) == key)
+            .map(|(_, v)| v)
+            .or_else(|| {
+                self.other
+                    .iter()
+                    .find(|(n, _)| canonical_lamp_name(n) == key)
+                    .map(|(_, v)| *v)
+            })
+    }
+
+    /// Returns all lamps as a name -> intensity map.
+    /// Typed lamps are listed under their canonical payload name.
+    pub fn all(&self) -> BTreeMap<String, f32> {
+        let mut all = self.other.clone();
+        for (name, value) in self.typed() {
+            all.insert(name.to_string(), value);
+        }
+        all
+    }
 }
 
 /// Represents a button in the vehicle and its current state.
-#[derive(Deserialize, Debug, PartialEq, Default, Clone)]
+#[derive(Deserialize, Serialize, Debug, PartialEq, Default, Clone)]
 pub struct ApiButton {
     /// Button name.
     #[serde(rename = "Name")]
@@ -218,8 +486,146 @@ pub struct ApiButton {
     pub states: Vec<String>,
 }
 
-impl ApiVehicleType {
+/// Represents a passenger door of the vehicle.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Default, Clone)]
+pub struct ApiDoor {
+    /// Door name (e.g. "Door Front").
+    #[serde(rename = "Name")]
+    pub name: String,
+    /// Whether the door is open (string "true"/"false").
+    #[serde(rename = "Open", default)]
+    pub open: String,
+    /// Opening progress as string (0.0: closed, 1.0: open).
+    #[serde(rename = "Progress", default)]
+    pub progress: String,
+    /// Whether a stop is requested at this door (string "true"/"false").
+    #[serde(rename = "StopRequest", default)]
+    pub stop_request: String,
+}
+
+/// Represents the gearbox state. Models without a gearbox only report `CurrentSelector`.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Default, Clone)]
+pub struct ApiGearbox {
+    /// Engaged gear (0: neutral, negative: reverse).
+    #[serde(rename = "CurrentGear", default)]
+    pub current_gear: i32,
+    /// Gear the gearbox is shifting to.
+    #[serde(rename = "TargetGear", default)]
+    pub target_gear: i32,
+    /// Number of forward gears.
+    #[serde(rename = "GearCount", default)]
+    pub gear_count: u32,
+    /// Selector position (e.g. "D", "N", "R").
+    #[serde(rename = "CurrentSelector", default)]
+    pub current_selector: String,
+}
+
+/// Represents the game logic state of the vehicle.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Default, Clone)]
+pub struct ApiBusLogic {
+    /// Ticket sales by id. Kept as raw JSON, see `ApiSale` for the typed view.
+    #[serde(rename = "Sales", default)]
+    pub sales: BTreeMap<String, serde_json::Value>,
+}
+
+/// Represents a ticket sale.
+///
+/// All recorded payloads so far carry an empty `Sales` object, so the field names
+/// "Ticket" and "Price" are assumptions. Both are required, so a sale using other names
+/// fails to parse instead of reading as an empty ticket; all other fields are kept in `other`.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Default, Clone)]
+pub struct ApiSale {
+    /// Name of the ticket.
+    #[serde(rename = "Ticket")]
+    pub ticket: String,
+    /// Price of the ticket in euros.
+    #[serde(rename = "Price")]
+    pub price: f32,
+    /// All other fields of the sale.
+    #[serde(flatten)]
+    pub other: BTreeMap<String, serde_json::Value>,
+}
+
+impl ApiButton {
+    /// Returns the first action of the button that triggers something (skipping "None").
+    pub fn action(&self) -> Option<&str> {
+        self.actions
+            .iter()
+            .map(String::as_str)
+            .find(|a| !a.is_empty() && *a != "None")
+    }
+
+    /// Reads the state of a toggle button: "Secondary", "true" and "On" are on,
+    /// "Primary", "false" and "Off" are off. Returns `None` for other states.
+    pub fn switch_state(&self) -> Option<bool> {
+        match self.state.to_ascii_lowercase().as_str() {
+            "secondary" | "true" | "on" => Some(true),
+            "primary" | "false" | "off" => Some(false),
+            _ => None,
+        }
+    }
+}
+
+impl ApiBusLogic {
+    /// Returns all sales read as `ApiSale` by id, with the parse error for sales that
+    /// don't match it. The raw values stay available in `sales`.
+    pub fn typed_sales(&self) -> Vec<(String, Result<ApiSale, serde_json::Error>)> {
+        self.sales
+            .iter()
+            .map(|(id, value)| (id.clone(), serde_json::from_value(value.clone())))
+            .collect()
+    }
+}
+
+/// Represents a position in world coordinates.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Default, Clone)]
+pub struct ApiLocation {
+    #[serde(rename = "X", default)]
+    pub x: f64,
+    #[serde(rename = "Y", default)]
+    pub y: f64,
+    #[serde(rename = "Z", default)]
+    pub z: f64,
+}
+
+/// Represents an orientation in degrees.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Default, Clone)]
+pub struct ApiRotation {
+    #[serde(rename = "Roll", default)]
+    pub roll: f32,
+    #[serde(rename = "Pitch", default)]
+    pub pitch: f32,
+    #[serde(rename = "Yaw", default)]
+    pub yaw: f32,
+}
+
+/// Represents a wheel of the vehicle.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Default, Clone)]
+pub struct ApiWheel {
+    /// Current suspension travel.
+    #[serde(rename = "CurrentSuspension", default)]
+    pub current_suspension: f32,
+    /// Suspension travel of the previous frame.
+    #[serde(rename = "PreviousSuspension", default)]
+    pub previous_suspension: f32,
+    /// Position of the wheel in the world.
+    #[serde(rename = "Location", default)]
+    pub location: ApiLocation,
+    /// Orientation of the wheel in degrees.
+    #[serde(rename = "Rotation", default)]
+    pub rotation: ApiRotation,
+    /// Force on the suspension.
+    #[serde(rename = "SuspensionForce", default)]
+    pub suspension_force: f64,
+    /// Whether the wheel touches the ground (string "true"/"false").
+    #[serde(rename = "OnGround", default)]
+    pub on_ground: String,
+    /// Whether the suspension is compressed (string "true"/"false").
+    #[serde(rename = "HasContraction", default)]
+    pub has_contraction: String,
+}
 
+impl ApiVehicleType {
     pub fn new() -> Self {
         Self::default()
     }
@@ -273,13 +679,23 @@ impl ApiVehicleType {
 pub async fn send_telemetry_bus_cmd(
     config: &RequestConfig,
     cmd: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    send_telemetry_path_cmd(config, &format!("vehicles/{}/{}", config.vehicle_name, cmd)).await
+}
+
+/// Sends a command path relative to the API root, e.g. below a path of the `UMG` map.
+pub async fn send_telemetry_path_cmd(
+    config: &RequestConfig,
+    path: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let url = format!(
-        "http://{}:{}/vehicles/{}/{}",
-        config.host, config.port, config.vehicle_name, cmd
+        "http://{}:{}/{}",
+        config.host,
+        config.port,
+        path.trim_start_matches('/')
     );
     if config.debugging {
-        println!("send_telemetry_bus_cmd URL: {}", url);
+        println!("send_telemetry_path_cmd URL: {}", url);
     }
 
     let _ = reqwest::Client::new()
@@ -291,6 +707,61 @@ pub async fn send_telemetry_bus_cmd(
     Ok(())
 }
 
+/// Triggers a button action (e.g. "Coins20" or "Take Cash Money") by pressing and releasing it.
+pub async fn send_telemetry_bus_event(
+    config: &RequestConfig,
+    event: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    send_telemetry_bus_event_press(config, event).await?;
+    send_telemetry_bus_event_release(config, event).await
+}
+
+/// Presses a button action and holds it until `send_telemetry_bus_event_release`.
+pub async fn send_telemetry_bus_event_press(
+    config: &RequestConfig,
+    event: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let cmd = format!("sendeventpress?event={}", encode_query_value(event));
+    send_telemetry_bus_cmd(config, &cmd).await
+}
+
+/// Releases a button action pressed with `send_telemetry_bus_event_press`.
+pub async fn send_telemetry_bus_event_release(
+    config: &RequestConfig,
+    event: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let cmd = format!("sendeventrelease?event={}", encode_query_value(event));
+    send_telemetry_bus_cmd(config, &cmd).await
+}
+
+/// Sets the state of a button, e.g. for buttons without actions like the radio keypad.
+pub async fn set_telemetry_bus_button(
+    config: &RequestConfig,
+    button: &str,
+    state: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let cmd = format!(
+        "setbutton?button={}&state={}",
+        encode_query_value(button),
+        encode_query_value(state)
+    );
+    send_telemetry_bus_cmd(config, &cmd).await
+}
+
+/// Percent-encodes a query parameter value.
+fn encode_query_value(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for b in value.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(b as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
 /// Fetches raw JSON telemetry data from a specific API path.
 pub async fn get_telemetry_data(
     config: &RequestConfig,
@@ -316,7 +787,7 @@ pub async fn get_telemetry_data(
 /// Returns the current vehicle name from the "player" telemetry endpoint.
 /// Returns an empty string if the player is not in a vehicle or if the request fails.
 pub async fn get_current_vehicle_name(config: &RequestConfig) -> String {
-    let result = get_telemetry_data(&config, "player").await;
+    let result = get_telemetry_data(config, "player").await;
 
     if result.is_err() {
         return "".to_string();
@@ -364,7 +835,7 @@ pub async fn get_vehicle(
         println!("get_vehicle path: {}", path);
     }
 
-    let body = get_telemetry_data(&config, &path).await?;
+    let body = get_telemetry_data(config, &path).await?;
 
     let api_vehicle: ApiVehicleType = serde_json::from_value(body).map_err(|e| {
         eprintln!("Failed to parse API response as Vehicle JSON: {}", e);
@@ -378,6 +849,32 @@ pub async fn get_vehicle(
     Ok(api_vehicle)
 }
 
+/// Fetches telemetry data for the vehicle specified in `config`, applying a mapping profile
+/// to lamp and button names before deserialization.
+pub async fn get_vehicle_with_profile(
+    config: &RequestConfig,
+    profile: &MappingProfile,
+) -> Result<ApiVehicleType, Box<dyn std::error::Error>> {
+    let path = format!("vehicles/{}", config.vehicle_name);
+
+    if config.debugging {
+        println!("get_vehicle_with_profile path: {}", path);
+    }
+
+    let body = get_telemetry_data(config, &path).await?;
+
+    let api_vehicle = profile.parse_vehicle(body).map_err(|e| {
+        eprintln!("Failed to parse API response as Vehicle JSON: {}", e);
+        Box::new(e) as Box<dyn std::error::Error>
+    })?;
+
+    if config.debugging {
+        println!("{:?}", &api_vehicle);
+    }
+
+    Ok(api_vehicle)
+}
+
 /// Fetches world telemetry data (time, weather, etc).
 pub async fn get_world(config: &RequestConfig) -> Result<ApiWorldType, Box<dyn std::error::Error>> {
     let path = "world";
@@ -386,7 +883,7 @@ pub async fn get_world(config: &RequestConfig) -> Result<ApiWorldType, Box<dyn s
         println!("get_world path: {}", path);
     }
 
-    let body = get_telemetry_data(&config, &path).await?;
+    let body = get_telemetry_data(config, path).await?;
 
     let api_world: ApiWorldType = serde_json::from_value(body).map_err(|e| {
         eprintln!("Failed to parse API response as World JSON: {}", e);
@@ -409,12 +906,12 @@ pub fn get_button_by_name(data: &serde_json::Value, name: &str) -> String {
                 entry
                     .get("Name")
                     .and_then(|n| n.as_str())
-                    .map_or(false, |s| s == name)
+                    .is_some_and(|s| s == name)
             })
         })
         .and_then(|entry| entry.get("State"))
         .and_then(|v| v.as_str())
         .map(|s| s.to_string());
 
-    ret.unwrap_or_else(|| "".to_string())
+    ret.unwrap_or_default()
 }
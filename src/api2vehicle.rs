@@ -1,10 +1,27 @@
 //! This module provides functions to map API-specific telemetry data to type-safe komsi vehicle states.
 
 use crate::api::ApiVehicleType;
+use crate::gearbox::GearSelector;
+use crate::lighting::LightingState;
+use crate::profile::{GEAR_SELECTOR, MappingProfile};
 use komsi::vehicle::VehicleState;
+use std::sync::LazyLock;
+
+static BUILTIN_PROFILE: LazyLock<MappingProfile> = LazyLock::new(MappingProfile::builtin);
 
 /// Maps `ApiVehicleType` data to a `VehicleState` structure.
 pub fn get_vehicle_state_from_api(av: ApiVehicleType) -> VehicleState {
+    get_vehicle_state_from_api_with_profile(av, &BUILTIN_PROFILE)
+}
+
+/// Maps `ApiVehicleType` data to a `VehicleState` structure using the state tables of `profile`.
+///
+/// Lamp and button names of the profile must already be applied to the payload,
+/// see `MappingProfile::parse_vehicle`.
+pub fn get_vehicle_state_from_api_with_profile(
+    av: ApiVehicleType,
+    profile: &MappingProfile,
+) -> VehicleState {
     let mut s = VehicleState::default();
 
     match av.ignition_enabled.as_str() {
@@ -40,21 +57,23 @@ pub fn get_vehicle_state_from_api(av: ApiVehicleType) -> VehicleState {
         _ => s.indicator = 0,
     }
 
-    let gear_selector = av.get_button_state("Gear Selector");
-    match gear_selector.as_str() {
-        "Drive" => s.gear_selector = 1,
-        "Neutral" => s.gear_selector = 2,
-        "Reverse" => s.gear_selector = 3,
-        _ => s.gear_selector = 2,
-    }
+    // Drive, Neutral, Reverse -> 1, 2, 3; states missing in the profile are read by
+    // GearSelector (push buttons, gearbox), anything else falls back to neutral
+    let gear_selector = av.get_button_state(GEAR_SELECTOR);
+    s.gear_selector = profile
+        .state_value(GEAR_SELECTOR, &gear_selector)
+        .or_else(|| GearSelector::from_vehicle(&av).map(|g| g.komsi_value()))
+        .unwrap_or(2);
 
     s.speed = av.speed.abs().round() as u32;
     s.maxspeed = av.allowed_speed.abs().round() as u32;
 
     s.fuel = (av.display_fuel * 100.0).trunc() as u8;
 
-    s.lights_main = av.all_lamps.light_parking > 0.0 || av.all_lamps.light_headlight > 0.0;
-    s.lights_high_beam = av.all_lamps.traveller_light > 0.0;
+    // komsi has no separate parking, fog, brake or reverse lights
+    let lighting = LightingState::from_vehicle(&av);
+    s.lights_main = lighting.parking.on || lighting.headlight.on;
+    s.lights_high_beam = lighting.high_beam.on;
     s.lights_front_door = av.all_lamps.front_door_light > 0.0;
     s.lights_second_door = av.all_lamps.second_door_light > 0.0;
     s.lights_third_door = av.all_lamps.third_door_light > 0.0;
@@ -0,0 +1,82 @@
+//! Prometheus exporter for The Bus telemetry.
+//!
+//! Polls the game in the background and serves the latest gauges on `/metrics`.
+//!
+//! Usage: `the-bus-metrics [--listen 0.0.0.0:9184] [--host 127.0.0.1] [--port 37337] [--interval-ms 1000]`
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use the_bus_telemetry::api::{RequestConfig, get_vehicle, get_world};
+use the_bus_telemetry::metrics::render_metrics;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut listen = "0.0.0.0:9184".to_string();
+    let mut config = RequestConfig::new();
+    let mut interval = Duration::from_millis(1000);
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = args.next().ok_or(format!("missing value for {}", arg))?;
+        match arg.as_str() {
+            "--listen" => listen = value,
+            "--host" => config = config.host(value),
+            "--port" => config = config.port(value),
+            "--interval-ms" => interval = Duration::from_millis(value.parse()?),
+            _ => return Err(format!("unknown argument {}", arg).into()),
+        }
+    }
+
+    let metrics = Arc::new(Mutex::new(render_metrics(None, None)));
+
+    let listener = TcpListener::bind(&listen).await?;
+    println!("serving metrics on http://{}/metrics", listen);
+
+    let served = Arc::clone(&metrics);
+    tokio::spawn(async move {
+        loop {
+            let Ok((stream, _)) = listener.accept().await else {
+                continue;
+            };
+            let body = served.lock().map(|m| m.clone()).unwrap_or_default();
+            tokio::spawn(handle_connection(stream, body));
+        }
+    });
+
+    // poll in one place only, scrapes never reach the game
+    loop {
+        let vehicle = get_vehicle(&config).await.ok();
+        let world = get_world(&config).await.ok();
+
+        let rendered = render_metrics(vehicle.as_ref(), world.as_ref());
+        if let Ok(mut m) = metrics.lock() {
+            *m = rendered;
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, body: String) {
+    let mut buf = [0u8; 4096];
+    let Ok(n) = stream.read(&mut buf).await else {
+        return;
+    };
+    let request = String::from_utf8_lossy(&buf[..n]);
+    let path = request.split_whitespace().nth(1).unwrap_or("");
+
+    let response = if path == "/metrics" {
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+    } else {
+        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+    };
+
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
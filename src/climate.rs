@@ -0,0 +1,211 @@
+//! This module reads the climate control buttons and sends climate commands.
+//!
+//! Toggle buttons report "Primary"/"Secondary" on some models and "true"/"false" on others;
+//! "Secondary", "true" and "On" are read as switched on. Commands use the actions of the
+//! buttons in the payload, so they work on every model that has the button. Use
+//! `set_climate_switch` to switch to a given state instead of toggling blindly.
+
+use crate::api::{ApiVehicleType, ApiWorldType, RequestConfig, send_telemetry_bus_event};
+
+/// Button with the target temperature in tenths of a degree (e.g. "200" = 20.0 °C).
+const TEMPERATURE_BUTTON: &str = "Air Condition Temperature";
+/// Lowest and highest target temperatures of the `SetTemp` actions.
+const MIN_TEMPERATURE: f32 = 18.0;
+const MAX_TEMPERATURE: f32 = 28.0;
+
+/// A climate switch.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ClimateSwitch {
+    AirCondition,
+    AcDriver,
+    AcReheat,
+    AcAdditionalHeating,
+    PodestHeating,
+    WindowHeating1,
+    WindowHeating2,
+}
+
+impl ClimateSwitch {
+    /// Returns the name of the button of this switch.
+    pub fn button_name(&self) -> &'static str {
+        match self {
+            ClimateSwitch::AirCondition => "Air Condition",
+            ClimateSwitch::AcDriver => "AC Driver",
+            ClimateSwitch::AcReheat => "AC Reheat",
+            ClimateSwitch::AcAdditionalHeating => "AC AdditionalHeating",
+            ClimateSwitch::PodestHeating => "Podest Heating",
+            ClimateSwitch::WindowHeating1 => "WindowHeating 1",
+            ClimateSwitch::WindowHeating2 => "WindowHeating 2",
+        }
+    }
+}
+
+/// Climate state of the vehicle. Fields are `None` if the vehicle lacks the button.
+#[derive(Debug, PartialEq, Default, Clone)]
+pub struct ClimateState {
+    pub air_condition: Option<bool>,
+    /// Target temperature in °C, on models reporting it.
+    pub target_temperature: Option<f32>,
+    /// Raw state of "AC 1 Mode".
+    pub mode: Option<String>,
+    /// Fan speed as reported by "AC 1 FanSpeed" (state if numeric, otherwise value).
+    pub fan_speed: Option<f32>,
+    pub ac_driver: Option<bool>,
+    pub ac_reheat: Option<bool>,
+    pub ac_additional_heating: Option<bool>,
+    pub podest_heating: Option<bool>,
+    pub window_heating_1: Option<bool>,
+    pub window_heating_2: Option<bool>,
+    /// Outside temperature in °C from the world telemetry.
+    pub outside_temperature: Option<f32>,
+}
+
+impl ClimateState {
+    /// Reads the climate state from the vehicle and, if given, the world telemetry.
+    pub fn from_vehicle(v: &ApiVehicleType, world: Option<&ApiWorldType>) -> Self {
+        let switch =
+            |s: ClimateSwitch| v.get_button(s.button_name()).and_then(|b| b.switch_state());
+
+        Self {
+            air_condition: switch(ClimateSwitch::AirCondition),
+            target_temperature: v
+                .get_button(TEMPERATURE_BUTTON)
+                .and_then(|b| b.state.parse::<f32>().ok())
+                .map(|t| t / 10.0),
+            mode: v.get_button("AC 1 Mode").map(|b| b.state),
+            fan_speed: v.get_button("AC 1 FanSpeed").and_then(|b| {
+                b.state
+                    .parse::<f32>()
+                    .or_else(|_| b.value.parse::<f32>())
+                    .ok()
+            }),
+            ac_driver: switch(ClimateSwitch::AcDriver),
+            ac_reheat: switch(ClimateSwitch::AcReheat),
+            ac_additional_heating: switch(ClimateSwitch::AcAdditionalHeating),
+            podest_heating: switch(ClimateSwitch::PodestHeating),
+            window_heating_1: switch(ClimateSwitch::WindowHeating1),
+            window_heating_2: switch(ClimateSwitch::WindowHeating2),
+            outside_temperature: world.map(|w| w.temperature),
+        }
+    }
+
+    /// Returns the target minus the outside temperature, if both are known.
+    pub fn temperature_difference(&self) -> Option<f32> {
+        Some(self.target_temperature? - self.outside_temperature?)
+    }
+}
+
+/// Returns the event toggling a climate switch on this vehicle.
+pub fn toggle_event(v: &ApiVehicleType, switch: ClimateSwitch) -> Option<String> {
+    let button = v.get_button(switch.button_name())?;
+    button.action().map(str::to_string)
+}
+
+/// Returns the event switching a climate switch on (`on`) or off on this vehicle.
+///
+/// Returns `Ok(None)` if the switch already is in that state, so the switch is never
+/// toggled the wrong way. Fails if the vehicle does not report the state of the switch or
+/// has no action for it.
+pub fn switch_event(
+    v: &ApiVehicleType,
+    switch: ClimateSwitch,
+    on: bool,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let state = v
+        .get_button(switch.button_name())
+        .and_then(|b| b.switch_state())
+        .ok_or_else(|| {
+            format!(
+                "the state of \"{}\" is unknown on this vehicle",
+                switch.button_name()
+            )
+        })?;
+    if state == on {
+        return Ok(None);
+    }
+    let event = toggle_event(v, switch).ok_or_else(|| {
+        format!(
+            "\"{}\" can not be switched on this vehicle",
+            switch.button_name()
+        )
+    })?;
+    Ok(Some(event))
+}
+
+/// Returns the event setting the target temperature, rounded to 0.5 °C and limited to 18..28 °C.
+///
+/// Only models with `SetTemp` actions support absolute temperatures.
+pub fn temperature_event(v: &ApiVehicleType, celsius: f32) -> Option<String> {
+    let tenths = (celsius.clamp(MIN_TEMPERATURE, MAX_TEMPERATURE) * 2.0).round() as u32 * 5;
+    let event = format!("SetTemp{}", tenths);
+    v.get_button(TEMPERATURE_BUTTON)?
+        .actions
+        .contains(&event)
+        .then_some(event)
+}
+
+/// Returns the event raising (`up`) or lowering the target temperature one step.
+pub fn temperature_step_event(v: &ApiVehicleType, up: bool) -> Option<String> {
+    let candidates: &[&str] = if up {
+        &["AirconditionPlus", "AirconditionKeyUp"]
+    } else {
+        &["AirconditionMinus", "AirconditionKeyDown"]
+    };
+    let button = v.get_button(TEMPERATURE_BUTTON)?;
+    candidates
+        .iter()
+        .find(|c| button.actions.iter().any(|a| a == *c))
+        .map(|c| c.to_string())
+}
+
+/// Toggles a climate switch.
+pub async fn toggle_climate_switch(
+    config: &RequestConfig,
+    v: &ApiVehicleType,
+    switch: ClimateSwitch,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let event = toggle_event(v, switch).ok_or_else(|| {
+        format!(
+            "\"{}\" can not be switched on this vehicle",
+            switch.button_name()
+        )
+    })?;
+    send_telemetry_bus_event(config, &event).await
+}
+
+/// Switches a climate switch on (`on`) or off, doing nothing if it already is in that state.
+///
+/// The state is read from `v`, so pass a current snapshot.
+pub async fn set_climate_switch(
+    config: &RequestConfig,
+    v: &ApiVehicleType,
+    switch: ClimateSwitch,
+    on: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match switch_event(v, switch, on)? {
+        Some(event) => send_telemetry_bus_event(config, &event).await,
+        None => Ok(()),
+    }
+}
+
+/// Sets the target temperature in °C.
+pub async fn set_target_temperature(
+    config: &RequestConfig,
+    v: &ApiVehicleType,
+    celsius: f32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let event = temperature_event(v, celsius)
+        .ok_or("the target temperature can not be set on this vehicle")?;
+    send_telemetry_bus_event(config, &event).await
+}
+
+/// Raises (`up`) or lowers the target temperature one step.
+pub async fn step_target_temperature(
+    config: &RequestConfig,
+    v: &ApiVehicleType,
+    up: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let event = temperature_step_event(v, up)
+        .ok_or("the target temperature can not be changed on this vehicle")?;
+    send_telemetry_bus_event(config, &event).await
+}
@@ -0,0 +1,158 @@
+//! This module tracks fuel or energy consumption.
+//!
+//! `CurrentFuel` and `MaxFuel` are litres of diesel on combustion buses and kWh of battery
+//! charge on electric buses. The kind is detected from the vehicle model.
+
+use crate::api::ApiVehicleType;
+use crate::trip::TripComputer;
+use serde::Serialize;
+use std::time::Duration;
+
+/// Name parts of electric vehicle models, matched against `VehicleModel` and `ActorName`.
+const ELECTRIC_MODELS: &[&str] = &["eCitaro", "eCitybus"];
+/// Increases of `CurrentFuel` above this amount are counted as refuel or charge events,
+/// smaller ones (e.g. recuperation) are summed up as recovered energy.
+const REFUEL_THRESHOLD: f32 = 0.5;
+/// Minimum distance in km before consumption and range are reported.
+const MIN_DISTANCE_KM: f64 = 0.5;
+
+/// Kind of energy a bus runs on.
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum EnergyKind {
+    #[default]
+    Diesel,
+    Electric,
+}
+
+impl EnergyKind {
+    /// Detects the energy kind from the vehicle model.
+    pub fn from_vehicle(v: &ApiVehicleType) -> Self {
+        let electric = ELECTRIC_MODELS
+            .iter()
+            .any(|m| v.vehicle_model.contains(m) || v.actor_name.contains(m));
+        if electric {
+            EnergyKind::Electric
+        } else {
+            EnergyKind::Diesel
+        }
+    }
+
+    /// Returns the unit of `CurrentFuel` and `MaxFuel` ("l" or "kWh").
+    pub fn unit(&self) -> &'static str {
+        match self {
+            EnergyKind::Diesel => "l",
+            EnergyKind::Electric => "kWh",
+        }
+    }
+}
+
+/// A refuel or charge event.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct RefuelEvent {
+    /// Time of the snapshot that showed the increase, in seconds.
+    pub time_s: f64,
+    /// Added amount in litres or kWh.
+    pub amount: f64,
+}
+
+/// Summary of the consumption so far.
+#[derive(Serialize, Debug, PartialEq, Default, Clone)]
+pub struct ConsumptionReport {
+    pub energy: EnergyKind,
+    /// Unit of all amounts ("l" or "kWh").
+    pub unit: String,
+    /// Consumed amount in litres or kWh, not counting `recovered`.
+    pub consumed: f64,
+    /// Amount added by small increases below the refuel threshold (e.g. recuperation),
+    /// in litres or kWh.
+    pub recovered: f64,
+    /// Distance driven in km.
+    pub distance_km: f64,
+    /// Net consumption (`consumed - recovered`, at least 0) in litres or kWh per 100 km,
+    /// once enough distance was driven.
+    pub per_100km: Option<f64>,
+    /// Remaining range in km at the net consumption, once enough distance was driven.
+    pub range_km: Option<f64>,
+    /// Current fuel or battery charge.
+    pub current: f64,
+    /// Whether the low fuel warning is shown.
+    pub low_fuel_warning: bool,
+    pub refuels: Vec<RefuelEvent>,
+}
+
+/// Stateful consumption tracker fed by snapshots.
+#[derive(Debug, Default)]
+pub struct ConsumptionTracker {
+    energy: Option<EnergyKind>,
+    trip: TripComputer,
+    last_fuel: Option<f32>,
+    consumed: f64,
+    recovered: f64,
+    current: f64,
+    low_fuel_warning: bool,
+    refuels: Vec<RefuelEvent>,
+}
+
+impl ConsumptionTracker {
+    /// Creates a new tracker detecting the energy kind from the first snapshot.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the energy kind instead of detecting it.
+    pub fn energy(mut self, energy: EnergyKind) -> Self {
+        self.energy = Some(energy);
+        self
+    }
+
+    /// Adds a snapshot taken at `time`.
+    pub fn update(&mut self, time: Duration, v: &ApiVehicleType) {
+        if self.energy.is_none() {
+            self.energy = Some(EnergyKind::from_vehicle(v));
+        }
+        self.trip.update(time, v);
+
+        if let Some(last) = self.last_fuel {
+            let delta = v.current_fuel - last;
+            if delta > REFUEL_THRESHOLD {
+                self.refuels.push(RefuelEvent {
+                    time_s: time.as_secs_f64(),
+                    amount: delta as f64,
+                });
+            } else if delta > 0.0 {
+                self.recovered += delta as f64;
+            } else {
+                self.consumed -= delta as f64;
+            }
+        }
+
+        self.last_fuel = Some(v.current_fuel);
+        self.current = v.current_fuel as f64;
+        self.low_fuel_warning = v.low_fuel_warning.eq_ignore_ascii_case("true");
+    }
+
+    /// Returns the consumption report so far.
+    pub fn report(&self) -> ConsumptionReport {
+        let energy = self.energy.unwrap_or_default();
+        let distance_km = self.trip.report().distance_km;
+
+        let net = (self.consumed - self.recovered).max(0.0);
+        let per_100km = (distance_km >= MIN_DISTANCE_KM).then(|| net / distance_km * 100.0);
+        let range_km = per_100km
+            .filter(|c| *c > 0.0)
+            .map(|c| self.current / c * 100.0);
+
+        ConsumptionReport {
+            energy,
+            unit: energy.unit().to_string(),
+            consumed: self.consumed,
+            recovered: self.recovered,
+            distance_km,
+            per_100km,
+            range_km,
+            current: self.current,
+            low_fuel_warning: self.low_fuel_warning,
+            refuels: self.refuels.clone(),
+        }
+    }
+}
@@ -0,0 +1,279 @@
+//! This module reads the cruise control state and holds a target speed with the throttle.
+//!
+//! None of the known vehicles reports a cruise control button, so `CruiseController` holds
+//! the speed itself by pressing and releasing the throttle event. It only engages on vehicles
+//! with a button offering that event, and disengages as soon as the driver brakes, a
+//! passenger door opens or the fixing brake is applied.
+
+use crate::api::{
+    ApiVehicleType, RequestConfig, send_telemetry_bus_event, send_telemetry_bus_event_press,
+    send_telemetry_bus_event_release,
+};
+
+/// Default event pressed to accelerate.
+const THROTTLE_EVENT: &str = "Throttle";
+/// Names of cruise control and speed limiter buttons, compared case insensitively.
+const CRUISE_BUTTONS: &[&str] = &[
+    "Cruise Control",
+    "CruiseControl",
+    "Tempomat",
+    "Speed Limiter",
+    "SpeedLimiter",
+    "Limiter",
+];
+/// Brake input above which the driver is considered braking.
+const BRAKE_THRESHOLD: f32 = 0.05;
+/// The throttle is pressed again when the speed drops this far below the target (km/h).
+const SPEED_TOLERANCE: f32 = 1.0;
+
+/// Cruise control state of the vehicle.
+#[derive(Debug, PartialEq, Default, Clone)]
+pub struct CruiseState {
+    /// `CruiseControlActive` as reported by the vehicle.
+    pub active: bool,
+    /// Current speed in km/h.
+    pub speed: f32,
+    /// Allowed speed in km/h.
+    pub allowed_speed: f32,
+    /// Names of the cruise control and speed limiter buttons of the vehicle.
+    pub buttons: Vec<String>,
+}
+
+impl CruiseState {
+    /// Reads the cruise control state from the telemetry.
+    pub fn from_vehicle(v: &ApiVehicleType) -> Self {
+        Self {
+            active: v.cruise_control_active.eq_ignore_ascii_case("true"),
+            speed: v.speed.abs(),
+            allowed_speed: v.allowed_speed,
+            buttons: v
+                .buttons
+                .iter()
+                .filter(|b| is_cruise_button(&b.name))
+                .map(|b| b.name.clone())
+                .collect(),
+        }
+    }
+
+    /// Returns true if the speed exceeds the allowed speed.
+    pub fn is_speeding(&self) -> bool {
+        self.allowed_speed > 0.0 && self.speed > self.allowed_speed
+    }
+}
+
+/// Returns true for button names of a cruise control or speed limiter.
+fn is_cruise_button(name: &str) -> bool {
+    CRUISE_BUTTONS.iter().any(|b| b.eq_ignore_ascii_case(name))
+}
+
+/// Toggles the cruise control of the vehicle with the action of its first cruise control button.
+pub async fn toggle_cruise_control(
+    config: &RequestConfig,
+    v: &ApiVehicleType,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let event = v
+        .buttons
+        .iter()
+        .filter(|b| is_cruise_button(&b.name))
+        .find_map(|b| b.action().map(str::to_string))
+        .ok_or("this vehicle has no cruise control button")?;
+    send_telemetry_bus_event(config, &event).await
+}
+
+/// Throttle change requested by the `CruiseController`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CruiseAction {
+    /// Press the throttle.
+    Accelerate,
+    /// Release the throttle.
+    Coast,
+}
+
+/// Why the `CruiseController` disengaged.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DisengageReason {
+    Brake,
+    DoorsOpen,
+    FixingBrake,
+    /// `disengage` was called.
+    Manual,
+}
+
+/// Holds a target speed by pressing and releasing the throttle.
+///
+/// Feed it every snapshot with `update` (or `step`, which also sends the command). When the
+/// speed limiter is on, the target is capped at the allowed speed.
+#[derive(Debug)]
+pub struct CruiseController {
+    target_speed: f32,
+    limiter: bool,
+    throttle_event: String,
+    engaged: bool,
+    throttle: bool,
+    disengaged_by: Option<DisengageReason>,
+}
+
+impl Default for CruiseController {
+    fn default() -> Self {
+        Self {
+            target_speed: 0.0,
+            limiter: false,
+            throttle_event: THROTTLE_EVENT.to_string(),
+            engaged: false,
+            throttle: false,
+            disengaged_by: None,
+        }
+    }
+}
+
+impl CruiseController {
+    /// Creates a disengaged controller.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Caps the target speed at `AllowedSpeed`.
+    pub fn limiter(mut self, limiter: bool) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
+    /// Sets the event pressed to accelerate (default "Throttle").
+    pub fn throttle_event(mut self, event: &str) -> Self {
+        self.throttle_event = event.to_string();
+        self
+    }
+
+    /// Engages the controller with a target speed in km/h.
+    ///
+    /// Fails without engaging if no button of the vehicle offers the throttle event.
+    pub fn engage(
+        &mut self,
+        v: &ApiVehicleType,
+        target_speed: f32,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let has_throttle = v
+            .buttons
+            .iter()
+            .any(|b| b.actions.contains(&self.throttle_event));
+        if !has_throttle {
+            return Err(format!(
+                "this vehicle has no \"{}\" event to hold the speed with",
+                self.throttle_event
+            )
+            .into());
+        }
+
+        self.target_speed = target_speed.max(0.0);
+        self.engaged = true;
+        self.disengaged_by = None;
+        Ok(())
+    }
+
+    /// Disengages the controller. The next `update` releases the throttle if it is pressed.
+    pub fn disengage(&mut self) {
+        if self.engaged {
+            self.engaged = false;
+            self.disengaged_by = Some(DisengageReason::Manual);
+        }
+    }
+
+    /// Returns true while the controller holds the speed.
+    pub fn is_engaged(&self) -> bool {
+        self.engaged
+    }
+
+    /// Returns why the controller disengaged last, if it did.
+    pub fn disengaged_by(&self) -> Option<DisengageReason> {
+        self.disengaged_by
+    }
+
+    /// Returns the speed the controller holds, after the speed limiter.
+    pub fn effective_target(&self, v: &ApiVehicleType) -> f32 {
+        if self.limiter && v.allowed_speed > 0.0 {
+            self.target_speed.min(v.allowed_speed)
+        } else {
+            self.target_speed
+        }
+    }
+
+    /// Adds a snapshot and returns the throttle change to send, if any.
+    ///
+    /// The change is taken as sent; use `step` to only keep it once it was sent.
+    pub fn update(&mut self, v: &ApiVehicleType) -> Option<CruiseAction> {
+        let action = self.next_action(v);
+        if let Some(action) = action {
+            self.throttle = action == CruiseAction::Accelerate;
+        }
+        action
+    }
+
+    /// Adds a snapshot and sends the resulting throttle change.
+    ///
+    /// The throttle state only changes when the event was sent, so a failed send is
+    /// retried with the next snapshot.
+    pub async fn step(
+        &mut self,
+        config: &RequestConfig,
+        v: &ApiVehicleType,
+    ) -> Result<Option<CruiseAction>, Box<dyn std::error::Error>> {
+        let action = self.next_action(v);
+        if let Some(action) = action {
+            match action {
+                CruiseAction::Accelerate => {
+                    send_telemetry_bus_event_press(config, &self.throttle_event).await?
+                }
+                CruiseAction::Coast => {
+                    send_telemetry_bus_event_release(config, &self.throttle_event).await?
+                }
+            }
+            self.throttle = action == CruiseAction::Accelerate;
+        }
+        Ok(action)
+    }
+
+    /// Applies the safeguards and returns the throttle change needed, if any.
+    fn next_action(&mut self, v: &ApiVehicleType) -> Option<CruiseAction> {
+        if self.engaged
+            && let Some(reason) = safeguard(v)
+        {
+            self.engaged = false;
+            self.disengaged_by = Some(reason);
+        }
+
+        let throttle = if self.engaged {
+            let speed = v.speed.abs();
+            let target = self.effective_target(v);
+            if speed >= target {
+                false
+            } else if speed < target - SPEED_TOLERANCE {
+                true
+            } else {
+                self.throttle
+            }
+        } else {
+            false
+        };
+
+        if throttle == self.throttle {
+            None
+        } else if throttle {
+            Some(CruiseAction::Accelerate)
+        } else {
+            Some(CruiseAction::Coast)
+        }
+    }
+}
+
+/// Returns the reason to disengage, if any.
+fn safeguard(v: &ApiVehicleType) -> Option<DisengageReason> {
+    if v.brake > BRAKE_THRESHOLD {
+        Some(DisengageReason::Brake)
+    } else if v.passenger_doors_open.eq_ignore_ascii_case("true") {
+        Some(DisengageReason::DoorsOpen)
+    } else if v.fixing_brake.eq_ignore_ascii_case("true") {
+        Some(DisengageReason::FixingBrake)
+    } else {
+        None
+    }
+}
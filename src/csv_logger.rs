@@ -0,0 +1,377 @@
+//! This module flattens telemetry snapshots into CSV rows for offline analysis.
+//!
+//! Column names only depend on the data, not on the vehicle model:
+//! - `time_ms`: timestamp of the row in milliseconds
+//! - `vehicle.<field>`: scalar fields of `ApiVehicleType`
+//! - `lamp.<name>`: lamps, named by `canonical_lamp_name`
+//! - `lamps.extra`: model specific lamps without a column of their own, see `EXTRA_LAMPS_COLUMN`
+//! - `door.<index>.<field>` and `wheel.<index>.<field>`: doors and wheels by position
+//! - `world.<field>`: fields of `ApiWorldType`
+//!
+//! The header is the fixed list of `default_columns`, so files of different buses can be
+//! concatenated (e.g. with `pandas.concat`) and columns a bus does not have stay empty.
+//! Lamps get a column if they are typed in `ApiLamps` or known across models; all other
+//! lamps (e.g. "LED ABS") are written to `lamps.extra` instead of being dropped.
+
+use crate::api::{ApiLamps, ApiVehicleType, ApiWorldType, canonical_lamp_name, known_lamp_names};
+use std::io::{self, Write};
+use std::time::Duration;
+
+/// Column holding the lamps without a column of their own as `name=value` pairs separated
+/// by ";", e.g. "ledabs=0;lightinteriorlowerdeck=1".
+pub const EXTRA_LAMPS_COLUMN: &str = "lamps.extra";
+
+/// Number of doors and wheels listed in `default_columns`.
+const MAX_DOORS: usize = 4;
+const MAX_WHEELS: usize = 8;
+
+/// Returns the columns of all vehicle and world fields, the typed lamps and the lamps known
+/// across models, `EXTRA_LAMPS_COLUMN`, `MAX_DOORS` doors and `MAX_WHEELS` wheels.
+pub fn default_columns() -> Vec<String> {
+    let mut columns = vec!["time_ms".to_string()];
+    columns.extend(
+        flatten_vehicle(&ApiVehicleType::default())
+            .into_iter()
+            .map(|(c, _)| c)
+            .filter(|c| c.starts_with("vehicle.")),
+    );
+    let mut lamps: Vec<String> = known_lamp_names().map(str::to_string).collect();
+    for name in ApiLamps::default().all().keys() {
+        let name = canonical_lamp_name(name);
+        if !lamps.contains(&name) {
+            lamps.push(name);
+        }
+    }
+    columns.extend(lamps.iter().map(|name| format!("lamp.{}", name)));
+    columns.push(EXTRA_LAMPS_COLUMN.to_string());
+    for i in 0..MAX_DOORS {
+        for field in ["name", "open", "progress", "stop_request"] {
+            columns.push(format!("door.{}.{}", i, field));
+        }
+    }
+    for i in 0..MAX_WHEELS {
+        for field in [
+            "current_suspension",
+            "suspension_force",
+            "on_ground",
+            "has_contraction",
+        ] {
+            columns.push(format!("wheel.{}.{}", i, field));
+        }
+    }
+    columns.extend(
+        flatten_world(&ApiWorldType::default())
+            .into_iter()
+            .map(|(c, _)| c),
+    );
+    columns
+}
+
+/// Returns the columns and values of a vehicle snapshot.
+pub fn flatten_vehicle(v: &ApiVehicleType) -> Vec<(String, String)> {
+    let mut row = vec![
+        ("vehicle.actor_name".to_string(), v.actor_name.clone()),
+        ("vehicle.vehicle_model".to_string(), v.vehicle_model.clone()),
+        (
+            "vehicle.ignition_enabled".to_string(),
+            v.ignition_enabled.clone(),
+        ),
+        (
+            "vehicle.engine_started".to_string(),
+            v.engine_started.clone(),
+        ),
+        (
+            "vehicle.warning_lights".to_string(),
+            v.warning_lights.clone(),
+        ),
+        ("vehicle.daytime_light".to_string(), v.daytime_light.clone()),
+        ("vehicle.fog_light".to_string(), v.fog_light.clone()),
+        (
+            "vehicle.rear_fog_light".to_string(),
+            v.rear_fog_light.clone(),
+        ),
+        ("vehicle.main_light".to_string(), v.main_light.clone()),
+        (
+            "vehicle.dashboard_light".to_string(),
+            v.dashboard_light.clone(),
+        ),
+        ("vehicle.parking_light".to_string(), v.parking_light.clone()),
+        ("vehicle.headlight".to_string(), v.headlight.clone()),
+        (
+            "vehicle.traveller_light".to_string(),
+            v.traveller_light.clone(),
+        ),
+        (
+            "vehicle.passenger_doors_open".to_string(),
+            v.passenger_doors_open.clone(),
+        ),
+        ("vehicle.fixing_brake".to_string(), v.fixing_brake.clone()),
+        ("vehicle.speed".to_string(), v.speed.to_string()),
+        ("vehicle.rpm".to_string(), v.rpm.to_string()),
+        ("vehicle.steering".to_string(), v.steering.to_string()),
+        ("vehicle.throttle".to_string(), v.throttle.to_string()),
+        ("vehicle.brake".to_string(), v.brake.to_string()),
+        (
+            "vehicle.allowed_speed".to_string(),
+            v.allowed_speed.to_string(),
+        ),
+        (
+            "vehicle.cruise_control_active".to_string(),
+            v.cruise_control_active.clone(),
+        ),
+        (
+            "vehicle.destination_display_active".to_string(),
+            v.destination_display_active.clone(),
+        ),
+        (
+            "vehicle.display_fuel".to_string(),
+            v.display_fuel.to_string(),
+        ),
+        (
+            "vehicle.current_fuel".to_string(),
+            v.current_fuel.to_string(),
+        ),
+        ("vehicle.max_fuel".to_string(), v.max_fuel.to_string()),
+        (
+            "vehicle.low_fuel_warning".to_string(),
+            v.low_fuel_warning.clone(),
+        ),
+        ("vehicle.num_seats".to_string(), v.num_seats.to_string()),
+        (
+            "vehicle.num_occupied_seats".to_string(),
+            v.num_occupied_seats.to_string(),
+        ),
+        ("vehicle.is_at_stop".to_string(), v.is_at_stop.clone()),
+        (
+            "vehicle.is_radio_playing".to_string(),
+            v.is_radio_playing.clone(),
+        ),
+        (
+            "vehicle.active_radio_channel".to_string(),
+            v.active_radio_channel.clone(),
+        ),
+        (
+            "vehicle.radio_volume".to_string(),
+            v.radio_volume.to_string(),
+        ),
+        ("vehicle.wiper_level".to_string(), v.wiper_level.to_string()),
+        (
+            "vehicle.indicator_state".to_string(),
+            v.indicator_state.to_string(),
+        ),
+        (
+            "vehicle.indicator_value".to_string(),
+            v.indicator_value.to_string(),
+        ),
+        ("vehicle.gearbox_type".to_string(), v.gearbox_type.clone()),
+        (
+            "vehicle.gearbox.current_gear".to_string(),
+            v.gearbox.current_gear.to_string(),
+        ),
+        (
+            "vehicle.gearbox.target_gear".to_string(),
+            v.gearbox.target_gear.to_string(),
+        ),
+        (
+            "vehicle.gearbox.gear_count".to_string(),
+            v.gearbox.gear_count.to_string(),
+        ),
+        (
+            "vehicle.gearbox.current_selector".to_string(),
+            v.gearbox.current_selector.clone(),
+        ),
+        ("vehicle.location.x".to_string(), v.location.x.to_string()),
+        ("vehicle.location.y".to_string(), v.location.y.to_string()),
+        ("vehicle.location.z".to_string(), v.location.z.to_string()),
+        (
+            "vehicle.rotation.roll".to_string(),
+            v.rotation.roll.to_string(),
+        ),
+        (
+            "vehicle.rotation.pitch".to_string(),
+            v.rotation.pitch.to_string(),
+        ),
+        (
+            "vehicle.rotation.yaw".to_string(),
+            v.rotation.yaw.to_string(),
+        ),
+    ];
+
+    // lamps are keyed by canonical name, so naming variants of other models share a column
+    let mut lamps: Vec<(String, String)> = v
+        .all_lamps
+        .all()
+        .into_iter()
+        .map(|(name, value)| {
+            (
+                format!("lamp.{}", canonical_lamp_name(&name)),
+                value.to_string(),
+            )
+        })
+        .collect();
+    lamps.sort();
+    lamps.dedup_by(|a, b| a.0 == b.0);
+    row.extend(lamps);
+
+    for (i, door) in v.doors.iter().enumerate() {
+        row.push((format!("door.{}.name", i), door.name.clone()));
+        row.push((format!("door.{}.open", i), door.open.clone()));
+        row.push((format!("door.{}.progress", i), door.progress.clone()));
+        row.push((
+            format!("door.{}.stop_request", i),
+            door.stop_request.clone(),
+        ));
+    }
+
+    for (i, wheel) in v.wheels.iter().enumerate() {
+        row.push((
+            format!("wheel.{}.current_suspension", i),
+            wheel.current_suspension.to_string(),
+        ));
+        row.push((
+            format!("wheel.{}.suspension_force", i),
+            wheel.suspension_force.to_string(),
+        ));
+        row.push((format!("wheel.{}.on_ground", i), wheel.on_ground.clone()));
+        row.push((
+            format!("wheel.{}.has_contraction", i),
+            wheel.has_contraction.clone(),
+        ));
+    }
+
+    row
+}
+
+/// Returns the columns and values of a world snapshot.
+pub fn flatten_world(w: &ApiWorldType) -> Vec<(String, String)> {
+    vec![
+        ("world.level_name".to_string(), w.level_name.clone()),
+        ("world.date_time".to_string(), w.date_time.clone()),
+        ("world.time_factor".to_string(), w.time_factor.to_string()),
+        (
+            "world.base_latitude".to_string(),
+            w.base_latitude.to_string(),
+        ),
+        (
+            "world.base_longitude".to_string(),
+            w.base_longitude.to_string(),
+        ),
+        ("world.temperature".to_string(), w.temperature.to_string()),
+        ("world.wetness".to_string(), w.wetness.to_string()),
+        (
+            "world.rain_intensity".to_string(),
+            w.rain_intensity.to_string(),
+        ),
+        (
+            "world.snow_intensity".to_string(),
+            w.snow_intensity.to_string(),
+        ),
+        ("world.snow_pack".to_string(), w.snow_pack.to_string()),
+        (
+            "world.cloud_intensity".to_string(),
+            w.cloud_intensity.to_string(),
+        ),
+        (
+            "world.nebular_intensity".to_string(),
+            w.nebular_intensity.to_string(),
+        ),
+        (
+            "world.wind_intensity".to_string(),
+            w.wind_intensity.to_string(),
+        ),
+    ]
+}
+
+/// Writes one CSV row per snapshot to a `Write` sink.
+///
+/// The header is `default_columns` unless set with `with_columns`. Missing values are left
+/// empty. Lamps not in the header are written to `EXTRA_LAMPS_COLUMN` if the header has it,
+/// other values of columns not in the header are dropped.
+pub struct CsvLogger<W: Write> {
+    writer: W,
+    columns: Vec<String>,
+    header_written: bool,
+}
+
+impl<W: Write> CsvLogger<W> {
+    /// Creates a new logger with `default_columns`.
+    pub fn new(writer: W) -> Self {
+        Self::with_columns(writer, default_columns())
+    }
+
+    /// Creates a new logger with a fixed set of columns.
+    pub fn with_columns(writer: W, columns: Vec<String>) -> Self {
+        Self {
+            writer,
+            columns,
+            header_written: false,
+        }
+    }
+
+    /// Returns the columns of the header.
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Writes a row for the given snapshots. `time` is usually the time since the UNIX epoch.
+    pub fn log(
+        &mut self,
+        time: Duration,
+        vehicle: Option<&ApiVehicleType>,
+        world: Option<&ApiWorldType>,
+    ) -> io::Result<()> {
+        let mut row = vec![("time_ms".to_string(), time.as_millis().to_string())];
+        if let Some(v) = vehicle {
+            row.extend(flatten_vehicle(v));
+        }
+        if let Some(w) = world {
+            row.extend(flatten_world(w));
+        }
+        self.write_row(&row)
+    }
+
+    /// Writes a flattened row, writing the header first if needed.
+    pub fn write_row(&mut self, row: &[(String, String)]) -> io::Result<()> {
+        if !self.header_written {
+            let header: Vec<String> = self.columns.iter().map(|c| escape(c)).collect();
+            writeln!(self.writer, "{}", header.join(","))?;
+            self.header_written = true;
+        }
+
+        let extra_lamps: Vec<String> = row
+            .iter()
+            .filter(|(name, _)| name.starts_with("lamp.") && !self.columns.contains(name))
+            .map(|(name, value)| format!("{}={}", &name["lamp.".len()..], value))
+            .collect();
+
+        let values: Vec<String> = self
+            .columns
+            .iter()
+            .map(|c| {
+                if c == EXTRA_LAMPS_COLUMN {
+                    return escape(&extra_lamps.join(";"));
+                }
+                row.iter()
+                    .find(|(name, _)| name == c)
+                    .map(|(_, value)| escape(value))
+                    .unwrap_or_default()
+            })
+            .collect();
+        writeln!(self.writer, "{}", values.join(","))?;
+        self.writer.flush()
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Quotes a CSV field if needed.
+fn escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,180 @@
+//! This module reads the destination display and board computer (IBIS) data and sets line
+//! and destination codes, e.g. to mirror the in-game destination sign on an external display.
+//!
+//! The board computer is served at the "Atron" path of the `UMG` map. Its response is not
+//! documented and none was recorded, so the key names (e.g. "Line", "LineNumber") are
+//! assumptions; they are looked up anywhere in the JSON and the raw response is kept as well.
+//!
+//! The `setline` and `setdestination` commands below the same path are assumptions too and
+//! have not been verified against the game. The "BoardComputer" button only offers
+//! "Select Boardcomputer", so there is no button event to fall back to.
+
+use crate::api::{ApiVehicleType, RequestConfig, get_telemetry_data, send_telemetry_path_cmd};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Name of the board computer in the `UMG` map.
+const BOARD_COMPUTER_UMG: &str = "Atron";
+const NO_BOARD_COMPUTER: &str = "this vehicle has no board computer";
+
+const LINE_KEYS: &[&str] = &["Line", "LineNumber", "CurrentLine"];
+const ROUTE_KEYS: &[&str] = &["Route", "RouteNumber", "Course"];
+const DESTINATION_KEYS: &[&str] = &["Destination", "DestinationName", "DestinationText"];
+const DESTINATION_CODE_KEYS: &[&str] = &["DestinationCode", "DestinationNumber"];
+const NEXT_STOP_KEYS: &[&str] = &["NextStop", "NextStopName"];
+
+/// Line and destination data of the board computer.
+#[derive(Serialize, Debug, PartialEq, Default, Clone)]
+pub struct BoardComputerData {
+    pub line: Option<String>,
+    pub route: Option<String>,
+    pub destination: Option<String>,
+    pub destination_code: Option<String>,
+    pub next_stop: Option<String>,
+    /// The response as received.
+    pub raw: Value,
+}
+
+impl BoardComputerData {
+    /// Reads the board computer data from its JSON response.
+    pub fn from_value(value: &Value) -> Self {
+        Self {
+            line: find_key(value, LINE_KEYS),
+            route: find_key(value, ROUTE_KEYS),
+            destination: find_key(value, DESTINATION_KEYS),
+            destination_code: find_key(value, DESTINATION_CODE_KEYS),
+            next_stop: find_key(value, NEXT_STOP_KEYS),
+            raw: value.clone(),
+        }
+    }
+}
+
+/// Normalizes a key for comparison ("Line Number" equals "lineNumber").
+fn normalize_key(key: &str) -> String {
+    key.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Returns the first non-empty string or number stored under one of `keys`, searching
+/// objects and arrays depth-first.
+fn find_key(value: &Value, keys: &[&str]) -> Option<String> {
+    let keys: Vec<String> = keys.iter().map(|k| normalize_key(k)).collect();
+    find_normalized_key(value, &keys)
+}
+
+fn find_normalized_key(value: &Value, keys: &[String]) -> Option<String> {
+    match value {
+        Value::Object(map) => {
+            let direct = map.iter().find_map(|(k, v)| {
+                if !keys.contains(&normalize_key(k)) {
+                    return None;
+                }
+                match v {
+                    Value::String(s) if !s.is_empty() => Some(s.clone()),
+                    Value::Number(n) => Some(n.to_string()),
+                    _ => None,
+                }
+            });
+            direct.or_else(|| map.values().find_map(|v| find_normalized_key(v, keys)))
+        }
+        Value::Array(items) => items.iter().find_map(|v| find_normalized_key(v, keys)),
+        _ => None,
+    }
+}
+
+/// Returns the board computer path of the vehicle, relative to the API root.
+pub fn board_computer_path(v: &ApiVehicleType) -> Option<String> {
+    v.umg
+        .get(BOARD_COMPUTER_UMG)
+        .map(|p| p.trim_start_matches('/').to_string())
+}
+
+/// Fetches the board computer data of the vehicle.
+///
+/// The layout of the response is unverified, see the module documentation.
+pub async fn get_board_computer(
+    config: &RequestConfig,
+    v: &ApiVehicleType,
+) -> Result<BoardComputerData, Box<dyn std::error::Error>> {
+    let path = board_computer_path(v).ok_or(NO_BOARD_COMPUTER)?;
+    let body = get_telemetry_data(config, &path).await?;
+    Ok(BoardComputerData::from_value(&body))
+}
+
+/// What the destination display shows.
+#[derive(Serialize, Debug, PartialEq, Default, Clone)]
+pub struct DestinationDisplay {
+    /// `DestinationDisplayActive` as reported by the vehicle.
+    pub active: bool,
+    pub line: Option<String>,
+    pub route: Option<String>,
+    pub destination: Option<String>,
+    pub destination_code: Option<String>,
+}
+
+impl DestinationDisplay {
+    /// Combines the vehicle telemetry with the board computer data, if fetched.
+    pub fn from_vehicle(v: &ApiVehicleType, board_computer: Option<&BoardComputerData>) -> Self {
+        let field = |f: fn(&BoardComputerData) -> &Option<String>| {
+            board_computer.and_then(|b| f(b).clone())
+        };
+        Self {
+            active: v.destination_display_active.eq_ignore_ascii_case("true"),
+            line: field(|b| &b.line),
+            route: field(|b| &b.route),
+            destination: field(|b| &b.destination),
+            destination_code: field(|b| &b.destination_code),
+        }
+    }
+}
+
+/// Returns the path setting the line code on the board computer of the vehicle, relative to
+/// the API root. Returns `None` if the vehicle has no board computer or `code` is not numeric.
+///
+/// The `setline` command is an unverified assumption, see the module documentation.
+///
+/// IBIS line and destination codes are numbers; leading zeros are kept.
+pub fn line_command(v: &ApiVehicleType, code: &str) -> Option<String> {
+    code_command(&board_computer_path(v)?, "setline", "line", code)
+}
+
+/// Returns the path setting the destination code, see `line_command`.
+pub fn destination_command(v: &ApiVehicleType, code: &str) -> Option<String> {
+    code_command(
+        &board_computer_path(v)?,
+        "setdestination",
+        "destination",
+        code,
+    )
+}
+
+fn code_command(path: &str, command: &str, parameter: &str, code: &str) -> Option<String> {
+    (!code.is_empty() && code.chars().all(|c| c.is_ascii_digit()))
+        .then(|| format!("{}/{}?{}={}", path, command, parameter, code))
+}
+
+/// Sets the line code on the board computer with the unverified `setline` command.
+pub async fn set_line(
+    config: &RequestConfig,
+    v: &ApiVehicleType,
+    code: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let path = board_computer_path(v).ok_or(NO_BOARD_COMPUTER)?;
+    let cmd = code_command(&path, "setline", "line", code)
+        .ok_or_else(|| format!("invalid line code \"{}\"", code))?;
+    send_telemetry_path_cmd(config, &cmd).await
+}
+
+/// Sets the destination code on the board computer with the unverified `setdestination` command.
+pub async fn set_destination(
+    config: &RequestConfig,
+    v: &ApiVehicleType,
+    code: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let path = board_computer_path(v).ok_or(NO_BOARD_COMPUTER)?;
+    let cmd = code_command(&path, "setdestination", "destination", code)
+        .ok_or_else(|| format!("invalid destination code \"{}\"", code))?;
+    send_telemetry_path_cmd(config, &cmd).await
+}
@@ -0,0 +1,189 @@
+//! This module reads the gear selector and gearbox and detects selector changes and shifts.
+//!
+//! The selector position is read from the first source the vehicle has:
+//! the "Gear Selector" button, the push buttons "Gear Drive", "Gear Neutral" and
+//! "Gear Reverse", `Gearbox.CurrentSelector` and finally the sign of `Gearbox.CurrentGear`.
+
+use crate::api::{ApiVehicleType, RequestConfig, send_telemetry_bus_event};
+use crate::profile::GEAR_SELECTOR;
+use serde::Serialize;
+
+/// Push buttons selecting D, N and R directly.
+const PUSH_BUTTONS: &[(&str, GearSelector)] = &[
+    ("Gear Drive", GearSelector::Drive),
+    ("Gear Neutral", GearSelector::Neutral),
+    ("Gear Reverse", GearSelector::Reverse),
+];
+
+/// Position of the gear selector.
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum GearSelector {
+    Drive,
+    Neutral,
+    Reverse,
+}
+
+impl GearSelector {
+    /// Parses a selector state like "Drive", "D" or a manual gear ("2", "-1").
+    pub fn from_state(state: &str) -> Option<Self> {
+        if let Ok(gear) = state.trim().parse::<i32>() {
+            return Some(Self::from_gear(gear));
+        }
+        match state.trim().to_ascii_lowercase().as_str() {
+            "drive" | "d" => Some(GearSelector::Drive),
+            "neutral" | "n" => Some(GearSelector::Neutral),
+            "reverse" | "r" => Some(GearSelector::Reverse),
+            _ => None,
+        }
+    }
+
+    /// Returns the selector matching a gear number (0: neutral, negative: reverse).
+    pub fn from_gear(gear: i32) -> Self {
+        match gear {
+            0 => GearSelector::Neutral,
+            g if g < 0 => GearSelector::Reverse,
+            _ => GearSelector::Drive,
+        }
+    }
+
+    /// Reads the selector position from the telemetry.
+    pub fn from_vehicle(v: &ApiVehicleType) -> Option<Self> {
+        if let Some(selector) = v
+            .get_button(GEAR_SELECTOR)
+            .and_then(|b| Self::from_state(&b.state))
+        {
+            return Some(selector);
+        }
+        if let Some((_, selector)) = PUSH_BUTTONS.iter().find(|(name, _)| {
+            v.get_button(name)
+                .is_some_and(|b| b.switch_state() == Some(true))
+        }) {
+            return Some(*selector);
+        }
+        if let Some(selector) = Self::from_state(&v.gearbox.current_selector) {
+            return Some(selector);
+        }
+        (v.gearbox.gear_count > 0).then(|| Self::from_gear(v.gearbox.current_gear))
+    }
+
+    /// Returns the komsi gear selector value (1: drive, 2: neutral, 3: reverse).
+    pub fn komsi_value(&self) -> u8 {
+        match self {
+            GearSelector::Drive => 1,
+            GearSelector::Neutral => 2,
+            GearSelector::Reverse => 3,
+        }
+    }
+
+    /// Returns the action selecting this position (e.g. "SetGearD").
+    fn action(&self) -> &'static str {
+        match self {
+            GearSelector::Drive => "SetGearD",
+            GearSelector::Neutral => "SetGearN",
+            GearSelector::Reverse => "SetGearR",
+        }
+    }
+}
+
+/// Gearbox state of the vehicle.
+#[derive(Serialize, Debug, PartialEq, Default, Clone)]
+pub struct GearboxState {
+    /// `GearboxType` as reported by the vehicle.
+    pub kind: String,
+    pub selector: Option<GearSelector>,
+    pub current_gear: i32,
+    pub target_gear: i32,
+    pub gear_count: u32,
+    /// True while the gearbox shifts, i.e. the target gear is not engaged yet.
+    pub shifting: bool,
+}
+
+impl GearboxState {
+    /// Reads the gearbox state from the telemetry.
+    pub fn from_vehicle(v: &ApiVehicleType) -> Self {
+        Self {
+            kind: v.gearbox_type.clone(),
+            selector: GearSelector::from_vehicle(v),
+            current_gear: v.gearbox.current_gear,
+            target_gear: v.gearbox.target_gear,
+            gear_count: v.gearbox.gear_count,
+            shifting: v.gearbox.current_gear != v.gearbox.target_gear,
+        }
+    }
+}
+
+/// A change of the selector or the engaged gear.
+#[derive(Serialize, Debug, PartialEq, Clone, Copy)]
+pub enum GearEvent {
+    SelectorChanged {
+        from: Option<GearSelector>,
+        to: Option<GearSelector>,
+    },
+    /// The gearbox started shifting towards `to`.
+    ShiftStarted { from: i32, to: i32 },
+    /// `to` is engaged.
+    Shifted { from: i32, to: i32 },
+}
+
+/// Detects gear events from snapshots.
+#[derive(Debug, Default)]
+pub struct GearboxTracker {
+    last: Option<GearboxState>,
+}
+
+impl GearboxTracker {
+    /// Creates a new tracker.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a snapshot and returns the events since the previous one.
+    pub fn update(&mut self, v: &ApiVehicleType) -> Vec<GearEvent> {
+        let state = GearboxState::from_vehicle(v);
+        let mut events = Vec::new();
+
+        if let Some(last) = &self.last {
+            if state.selector != last.selector {
+                events.push(GearEvent::SelectorChanged {
+                    from: last.selector,
+                    to: state.selector,
+                });
+            }
+            if state.target_gear != last.target_gear && state.shifting {
+                events.push(GearEvent::ShiftStarted {
+                    from: state.current_gear,
+                    to: state.target_gear,
+                });
+            }
+            if state.current_gear != last.current_gear {
+                events.push(GearEvent::Shifted {
+                    from: last.current_gear,
+                    to: state.current_gear,
+                });
+            }
+        }
+
+        self.last = Some(state);
+        events
+    }
+}
+
+/// Returns the event moving the selector to `selector`, if a button of the vehicle offers it.
+pub fn selector_event(v: &ApiVehicleType, selector: GearSelector) -> Option<String> {
+    let action = selector.action();
+    v.buttons
+        .iter()
+        .any(|b| b.actions.iter().any(|a| a == action))
+        .then(|| action.to_string())
+}
+
+/// Moves the gear selector to `selector`.
+pub async fn select_gear(
+    config: &RequestConfig,
+    v: &ApiVehicleType,
+    selector: GearSelector,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let event = selector_event(v, selector)
+        .ok_or_else(|| format!("{:?} can not be selected on this vehicle", selector))?;
+    send_telemetry_bus_event(config, &event).await
+}
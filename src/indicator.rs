@@ -0,0 +1,130 @@
+//! This module reads the live blink phase of the indicators.
+//!
+//! `IndicatorState` only tells the direction. The phase is read from the indicator lamps
+//! (`IndicatorLeft`, `Light Indicator Left`, `LED IndicatorLeft`, ...), which are lit only
+//! while the indicator is on. Vehicles without indicator lamps fall back to `IndicatorValue`.
+//!
+//! The phase is only as accurate as the polling: poll well below the blink half period
+//! (about 350 ms) to catch every edge.
+
+use crate::api::ApiVehicleType;
+use serde::Serialize;
+use std::time::Duration;
+
+const LEFT_LAMPS: &[&str] = &["IndicatorLeft", "LED IndicatorLeft"];
+const RIGHT_LAMPS: &[&str] = &["IndicatorRight", "LED IndicatorRight"];
+
+/// What the indicators are set to.
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum IndicatorMode {
+    #[default]
+    Off,
+    Left,
+    Right,
+    /// Hazard lights, both sides blink.
+    Hazard,
+}
+
+impl IndicatorMode {
+    /// Reads the mode from `WarningLights` and `IndicatorState`; hazard lights take precedence.
+    pub fn from_vehicle(v: &ApiVehicleType) -> Self {
+        if v.warning_lights.eq_ignore_ascii_case("true") {
+            return IndicatorMode::Hazard;
+        }
+        match v.indicator_state {
+            -1 => IndicatorMode::Left,
+            1 => IndicatorMode::Right,
+            _ => IndicatorMode::Off,
+        }
+    }
+}
+
+/// Blink phase of the indicators.
+#[derive(Serialize, Debug, PartialEq, Default, Clone, Copy)]
+pub struct IndicatorPhase {
+    pub mode: IndicatorMode,
+    /// True while the left indicator is lit.
+    pub left: bool,
+    /// True while the right indicator is lit.
+    pub right: bool,
+}
+
+impl IndicatorPhase {
+    /// Reads the blink phase from the telemetry.
+    pub fn from_vehicle(v: &ApiVehicleType) -> Self {
+        let mode = IndicatorMode::from_vehicle(v);
+        let lamp = |names: &[&str]| {
+            names
+                .iter()
+                .filter_map(|n| v.all_lamps.get(n))
+                .reduce(f32::max)
+        };
+
+        let (left, right) = match (lamp(LEFT_LAMPS), lamp(RIGHT_LAMPS)) {
+            (None, None) => {
+                let lit = v.indicator_value > 0.0;
+                (
+                    lit && matches!(mode, IndicatorMode::Left | IndicatorMode::Hazard),
+                    lit && matches!(mode, IndicatorMode::Right | IndicatorMode::Hazard),
+                )
+            }
+            (left, right) => (left.unwrap_or(0.0) > 0.0, right.unwrap_or(0.0) > 0.0),
+        };
+
+        Self { mode, left, right }
+    }
+
+    /// Returns true if any indicator is lit.
+    pub fn lit(&self) -> bool {
+        self.left || self.right
+    }
+}
+
+/// A change of the blink phase, e.g. to click a relay.
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum BlinkEdge {
+    On,
+    Off,
+}
+
+/// Detects blink edges and measures the blink period from snapshots.
+#[derive(Debug, Default)]
+pub struct IndicatorTracker {
+    lit: bool,
+    last_on: Option<Duration>,
+    period: Option<Duration>,
+}
+
+impl IndicatorTracker {
+    /// Creates a new tracker.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a snapshot taken at `time` and returns the edge, if the phase changed.
+    pub fn update(&mut self, time: Duration, v: &ApiVehicleType) -> Option<BlinkEdge> {
+        let phase = IndicatorPhase::from_vehicle(v);
+        if phase.mode == IndicatorMode::Off {
+            self.last_on = None;
+            self.period = None;
+        }
+        if phase.lit() == self.lit {
+            return None;
+        }
+        self.lit = phase.lit();
+
+        if !self.lit {
+            return Some(BlinkEdge::Off);
+        }
+        if let Some(last) = self.last_on {
+            self.period = Some(time.saturating_sub(last));
+        }
+        self.last_on = Some(time);
+        Some(BlinkEdge::On)
+    }
+
+    /// Returns the time between the last two `On` edges of the current blinking.
+    pub fn period(&self) -> Option<Duration> {
+        self.period
+    }
+}
@@ -0,0 +1,277 @@
+//! This module serializes telemetry snapshots into the InfluxDB line protocol.
+//!
+//! Measurements (tags `actor` and `model` on all vehicle measurements):
+//! - `thebus_vehicle`: speed, rpm, switches and seats
+//! - `thebus_fuel`: fuel or battery level
+//! - `thebus_position`: location and rotation
+//! - `thebus_door`: one line per door, tagged with `door`
+//! - `thebus_wheel`: one line per wheel, tagged with `wheel` (index)
+//! - `thebus_lamp`: one field per lamp, split over several lines if they do not fit into one datagram
+//! - `thebus_world`: weather and time, tagged with `level`
+//!
+//! Timestamps are nanoseconds since the UNIX epoch, so write with `precision=ns`.
+
+use crate::api::{ApiVehicleType, ApiWorldType};
+use std::io::{self, Write};
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::time::Duration;
+
+/// Maximum size of a UDP datagram sent by `send_udp`.
+const MAX_DATAGRAM: usize = 1400;
+
+enum FieldValue {
+    Float(f64),
+    Int(i64),
+    Bool(bool),
+}
+
+/// Returns the line protocol lines of a vehicle snapshot.
+pub fn vehicle_lines(v: &ApiVehicleType, timestamp: Duration) -> Vec<String> {
+    let ts = timestamp.as_nanos();
+    let tags = [
+        ("actor", v.actor_name.as_str()),
+        ("model", v.vehicle_model.as_str()),
+    ];
+    let mut lines = Vec::new();
+
+    lines.extend(line(
+        "thebus_vehicle",
+        &tags,
+        &[
+            ("speed", FieldValue::Float(v.speed as f64)),
+            ("rpm", FieldValue::Float(v.rpm as f64)),
+            ("allowed_speed", FieldValue::Float(v.allowed_speed as f64)),
+            ("indicator_state", FieldValue::Int(v.indicator_state as i64)),
+            ("ignition_enabled", flag(&v.ignition_enabled)),
+            ("engine_started", flag(&v.engine_started)),
+            ("warning_lights", flag(&v.warning_lights)),
+            ("passenger_doors_open", flag(&v.passenger_doors_open)),
+            ("fixing_brake", flag(&v.fixing_brake)),
+            ("num_seats", FieldValue::Int(v.num_seats as i64)),
+            (
+                "num_occupied_seats",
+                FieldValue::Int(v.num_occupied_seats as i64),
+            ),
+        ],
+        ts,
+    ));
+
+    lines.extend(line(
+        "thebus_fuel",
+        &tags,
+        &[
+            ("display_fuel", FieldValue::Float(v.display_fuel as f64)),
+            ("current_fuel", FieldValue::Float(v.current_fuel as f64)),
+            ("max_fuel", FieldValue::Float(v.max_fuel as f64)),
+        ],
+        ts,
+    ));
+
+    lines.extend(line(
+        "thebus_position",
+        &tags,
+        &[
+            ("x", FieldValue::Float(v.location.x)),
+            ("y", FieldValue::Float(v.location.y)),
+            ("z", FieldValue::Float(v.location.z)),
+            ("roll", FieldValue::Float(v.rotation.roll as f64)),
+            ("pitch", FieldValue::Float(v.rotation.pitch as f64)),
+            ("yaw", FieldValue::Float(v.rotation.yaw as f64)),
+        ],
+        ts,
+    ));
+
+    for door in &v.doors {
+        let door_tags = [tags[0], tags[1], ("door", door.name.as_str())];
+        lines.extend(line(
+            "thebus_door",
+            &door_tags,
+            &[
+                ("open", flag(&door.open)),
+                (
+                    "progress",
+                    FieldValue::Float(door.progress.parse().unwrap_or(0.0)),
+                ),
+                ("stop_request", flag(&door.stop_request)),
+            ],
+            ts,
+        ));
+    }
+
+    for (i, wheel) in v.wheels.iter().enumerate() {
+        let index = i.to_string();
+        let wheel_tags = [tags[0], tags[1], ("wheel", index.as_str())];
+        lines.extend(line(
+            "thebus_wheel",
+            &wheel_tags,
+            &[
+                (
+                    "current_suspension",
+                    FieldValue::Float(wheel.current_suspension as f64),
+                ),
+                (
+                    "suspension_force",
+                    FieldValue::Float(wheel.suspension_force),
+                ),
+                ("on_ground", flag(&wheel.on_ground)),
+            ],
+            ts,
+        ));
+    }
+
+    let lamps = v.all_lamps.all();
+    let lamp_fields: Vec<(&str, FieldValue)> = lamps
+        .iter()
+        .map(|(name, value)| (name.as_str(), FieldValue::Float(*value as f64)))
+        .collect();
+    if !lamp_fields.is_empty() {
+        lines.extend(line("thebus_lamp", &tags, &lamp_fields, ts));
+    }
+
+    lines
+}
+
+/// Returns the line protocol line of a world snapshot.
+pub fn world_lines(w: &ApiWorldType, timestamp: Duration) -> Vec<String> {
+    line(
+        "thebus_world",
+        &[("level", w.level_name.as_str())],
+        &[
+            ("time_factor", FieldValue::Float(w.time_factor as f64)),
+            ("temperature", FieldValue::Float(w.temperature as f64)),
+            ("wetness", FieldValue::Float(w.wetness as f64)),
+            ("rain_intensity", FieldValue::Float(w.rain_intensity as f64)),
+            ("snow_intensity", FieldValue::Float(w.snow_intensity as f64)),
+            ("snow_pack", FieldValue::Float(w.snow_pack as f64)),
+            (
+                "cloud_intensity",
+                FieldValue::Float(w.cloud_intensity as f64),
+            ),
+            (
+                "nebular_intensity",
+                FieldValue::Float(w.nebular_intensity as f64),
+            ),
+            ("wind_intensity", FieldValue::Float(w.wind_intensity as f64)),
+        ],
+        timestamp.as_nanos(),
+    )
+}
+
+/// Writes lines to a file or any other `Write` sink.
+pub fn write_lines<W: Write>(writer: &mut W, lines: &[String]) -> io::Result<()> {
+    for l in lines {
+        writeln!(writer, "{}", l)?;
+    }
+    writer.flush()
+}
+
+/// Sends lines to the UDP listener of InfluxDB (or Telegraf), batching lines into datagrams.
+pub fn send_udp<A: ToSocketAddrs>(socket: &UdpSocket, addr: A, lines: &[String]) -> io::Result<()> {
+    let addr = addr
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no address"))?;
+
+    let mut datagram = String::new();
+    for l in lines {
+        if !datagram.is_empty() && datagram.len() + l.len() + 1 > MAX_DATAGRAM {
+            socket.send_to(datagram.as_bytes(), addr)?;
+            datagram.clear();
+        }
+        datagram.push_str(l);
+        datagram.push('\n');
+    }
+    if !datagram.is_empty() {
+        socket.send_to(datagram.as_bytes(), addr)?;
+    }
+    Ok(())
+}
+
+/// Posts lines to an HTTP write endpoint,
+/// e.g. "http://localhost:8086/api/v2/write?org=org&bucket=thebus&precision=ns".
+///
+/// `token` is sent as "Authorization: Token <token>" if given.
+pub async fn write_http(
+    url: &str,
+    token: Option<&str>,
+    lines: &[String],
+    timeout: Duration,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut request = reqwest::Client::new()
+        .post(url)
+        .timeout(timeout)
+        .body(lines.join("\n"));
+    if let Some(token) = token {
+        request = request.header("Authorization", format!("Token {}", token));
+    }
+
+    request.send().await?.error_for_status()?;
+    Ok(())
+}
+
+/// Formats one measurement.
+///
+/// Non-finite floats are skipped, because InfluxDB rejects the whole batch on them.
+/// The fields are split over several lines with the same tags and timestamp
+/// if a single line would not fit into a datagram of `send_udp`.
+fn line(
+    measurement: &str,
+    tags: &[(&str, &str)],
+    fields: &[(&str, FieldValue)],
+    ts: u128,
+) -> Vec<String> {
+    let mut out = escape(measurement, &[',', ' ']);
+    for (key, value) in tags {
+        // empty tag values are not allowed
+        if value.is_empty() {
+            continue;
+        }
+        out.push(',');
+        out.push_str(&escape(key, &[',', '=', ' ']));
+        out.push('=');
+        out.push_str(&escape(value, &[',', '=', ' ']));
+    }
+    let ts = ts.to_string();
+
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for (key, value) in fields {
+        let value = match value {
+            FieldValue::Float(f) if !f.is_finite() => continue,
+            FieldValue::Float(f) => f.to_string(),
+            FieldValue::Int(i) => format!("{}i", i),
+            FieldValue::Bool(b) => b.to_string(),
+        };
+        let field = format!("{}={}", escape(key, &[',', '=', ' ']), value);
+
+        if !current.is_empty()
+            && out.len() + current.len() + field.len() + ts.len() + 4 > MAX_DATAGRAM
+        {
+            lines.push(format!("{} {} {}", out, current, ts));
+            current.clear();
+        }
+        if !current.is_empty() {
+            current.push(',');
+        }
+        current.push_str(&field);
+    }
+    if !current.is_empty() {
+        lines.push(format!("{} {} {}", out, current, ts));
+    }
+    lines
+}
+
+fn flag(value: &str) -> FieldValue {
+    FieldValue::Bool(value.eq_ignore_ascii_case("true"))
+}
+
+fn escape(value: &str, special: &[char]) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        if c == '\\' || special.contains(&c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
@@ -0,0 +1,84 @@
+//! This module bridges telemetry to a KOMSI device (serial port or pseudo-terminal).
+//!
+//! `KomsiBridge` keeps the last written `VehicleState` and only writes the KOMSI commands
+//! of fields that changed. The sink is opened through a connect function, so a device that
+//! was unplugged is reopened on the next update and receives the complete state again.
+//!
+//! ```ignore
+//! let mut bridge = KomsiBridge::new(|| {
+//!     serialport::new("/dev/ttyUSB0", 115200)
+//!         .open()
+//!         .map_err(std::io::Error::from)
+//! });
+//! loop {
+//!     if let Err(e) = bridge.poll(&config).await {
+//!         eprintln!("{}", e);
+//!     }
+//!     sleep(Duration::from_millis(100)).await;
+//! }
+//! ```
+
+use crate::api::{RequestConfig, get_vehicle};
+use crate::api2vehicle::get_vehicle_state_from_api;
+use komsi::vehicle::VehicleState;
+use std::io::{self, Write};
+
+/// Writes changed vehicle states as KOMSI commands to a `Write` sink.
+pub struct KomsiBridge<W: Write, F: FnMut() -> io::Result<W>> {
+    connect: F,
+    sink: Option<W>,
+    last: VehicleState,
+    // after (re)connecting the device state is unknown, so all fields are written
+    force: bool,
+}
+
+impl<W: Write, F: FnMut() -> io::Result<W>> KomsiBridge<W, F> {
+    /// Creates a new bridge. `connect` is called whenever the sink has to be (re)opened.
+    pub fn new(connect: F) -> Self {
+        Self {
+            connect,
+            sink: None,
+            last: VehicleState::default(),
+            force: true,
+        }
+    }
+
+    /// Returns true if the sink is currently open.
+    pub fn is_connected(&self) -> bool {
+        self.sink.is_some()
+    }
+
+    /// Writes the commands for all fields that differ from the last written state.
+    ///
+    /// Returns the number of bytes written. On a write error the sink is dropped and
+    /// reopened on the next call.
+    pub fn update(&mut self, state: VehicleState) -> io::Result<usize> {
+        if self.sink.is_none() {
+            self.sink = Some((self.connect)()?);
+            self.force = true;
+        }
+
+        let buffer = self.last.compare(&state, self.force, None);
+        if !buffer.is_empty()
+            && let Some(sink) = self.sink.as_mut()
+            && let Err(e) = sink.write_all(&buffer).and_then(|_| sink.flush())
+        {
+            self.sink = None;
+            return Err(e);
+        }
+
+        self.last = state;
+        self.force = false;
+        Ok(buffer.len())
+    }
+
+    /// Fetches the current vehicle, maps it and writes the changes.
+    pub async fn poll(
+        &mut self,
+        config: &RequestConfig,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let vehicle = get_vehicle(config).await?;
+        let state = get_vehicle_state_from_api(vehicle);
+        Ok(self.update(state)?)
+    }
+}
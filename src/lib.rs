@@ -2,20 +2,104 @@
 //! It fetches telemetry data from the game "The Bus" and maps it to common vehicle states.
 
 // This file exposes the modules used by both binary targets and integration tests
+pub mod accessibility;
 pub mod api;
 pub mod api2vehicle;
+pub mod climate;
+pub mod consumption;
+pub mod cruise;
+pub mod csv_logger;
+pub mod destination;
+pub mod gearbox;
+pub mod indicator;
+pub mod influx;
+pub mod komsi_bridge;
+pub mod lighting;
+pub mod metrics;
+pub mod occupancy;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+pub mod profile;
+pub mod punctuality;
+pub mod radio;
+pub mod sales;
+pub mod score;
+pub mod trip;
+pub mod udp;
+pub mod validation;
+#[cfg(feature = "websocket")]
+pub mod websocket;
+pub mod wiper;
 
+pub use accessibility::AccessibilityState;
+
+pub use api::ApiBusLogic;
 pub use api::ApiButton;
+pub use api::ApiDoor;
+pub use api::ApiGearbox;
 pub use api::ApiLamps;
+pub use api::ApiLocation;
+pub use api::ApiRotation;
+pub use api::ApiSale;
 pub use api::ApiVehicleType;
+pub use api::ApiWheel;
 pub use api::ApiWorldType;
 pub use api::RequestConfig;
 pub use api::get_current_vehicle_name;
 pub use api::get_vehicle;
+pub use api::get_vehicle_with_profile;
 pub use api::get_world;
 pub use api::send_telemetry_bus_cmd;
+pub use api::send_telemetry_bus_event;
+pub use api::send_telemetry_bus_event_press;
+pub use api::send_telemetry_bus_event_release;
+pub use api::send_telemetry_path_cmd;
+pub use api::set_telemetry_bus_button;
 pub use api::get_telemetry_data;
 pub use api::get_button_by_name;
+pub use api::canonical_lamp_name;
 
 pub use api2vehicle::get_vehicle_state_from_api;
+pub use api2vehicle::get_vehicle_state_from_api_with_profile;
+
+pub use climate::ClimateState;
+
+pub use consumption::ConsumptionTracker;
+
+pub use cruise::CruiseController;
+
+pub use csv_logger::CsvLogger;
+
+pub use destination::DestinationDisplay;
+
+pub use gearbox::GearboxState;
+
+pub use indicator::IndicatorPhase;
+
+pub use komsi_bridge::KomsiBridge;
+
+pub use lighting::LightingState;
+
+pub use occupancy::OccupancyTracker;
+
+pub use profile::MappingProfile;
+pub use profile::ProfileError;
+
+pub use punctuality::PunctualityTracker;
+pub use punctuality::Timetable;
+
+pub use radio::RadioKey;
+pub use radio::RadioState;
+
+pub use sales::RevenueLedger;
+
+pub use score::DrivingScore;
+
+pub use trip::TripComputer;
+pub use trip::TripReport;
+
+pub use validation::ValidationReport;
+pub use validation::validate_vehicle_payload;
+pub use validation::validate_world_payload;
 
+pub use wiper::WiperState;
@@ -0,0 +1,169 @@
+//! This module reads the exterior lights and the light switch and moves the light switch.
+//!
+//! Lamps are read from `AllLamps` by canonical name, so naming variants like "LightFog1"
+//! and "Light Front Fog" are found on every model. Where the vehicle also reports a
+//! top-level flag (e.g. `FogLight`), a lamp counts as on if either says so.
+//!
+//! komsi only knows the main and high beam lights, see `api2vehicle`.
+
+use crate::api::{ApiVehicleType, RequestConfig, send_telemetry_bus_event};
+use serde::Serialize;
+
+const LIGHT_SWITCH_BUTTON: &str = "Light Switch";
+const LIGHT_SWITCH_UP: &str = "LightSwitchUp";
+const LIGHT_SWITCH_DOWN: &str = "LightSwitchDown";
+
+/// Position of the light switch.
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LightSwitchPosition {
+    Off,
+    ParkingLights,
+    Headlights,
+    FrontFogLight,
+    RearFogLight,
+}
+
+impl LightSwitchPosition {
+    /// Parses a state of the "Light Switch" button (e.g. "Parking Lights").
+    pub fn from_state(state: &str) -> Option<Self> {
+        let state: String = state
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        match state.as_str() {
+            "off" => Some(LightSwitchPosition::Off),
+            "parkinglights" => Some(LightSwitchPosition::ParkingLights),
+            "headlights" => Some(LightSwitchPosition::Headlights),
+            "frontfoglight" => Some(LightSwitchPosition::FrontFogLight),
+            "rearfoglight" => Some(LightSwitchPosition::RearFogLight),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of steps up from `Off` to this position.
+    pub fn step(&self) -> u32 {
+        match self {
+            LightSwitchPosition::Off => 0,
+            LightSwitchPosition::ParkingLights => 1,
+            LightSwitchPosition::Headlights => 2,
+            LightSwitchPosition::FrontFogLight => 3,
+            LightSwitchPosition::RearFogLight => 4,
+        }
+    }
+}
+
+/// State of one lamp.
+#[derive(Serialize, Debug, PartialEq, Default, Clone, Copy)]
+pub struct Lamp {
+    pub on: bool,
+    /// Intensity as reported in `AllLamps` (0 if the vehicle lacks the lamp).
+    pub intensity: f32,
+}
+
+impl Lamp {
+    fn new(intensity: f32, flag: bool) -> Self {
+        Self {
+            on: intensity > 0.0 || flag,
+            intensity,
+        }
+    }
+}
+
+/// Exterior lights of the vehicle.
+#[derive(Serialize, Debug, PartialEq, Default, Clone)]
+pub struct LightingState {
+    /// Position of the light switch, if the vehicle reports it.
+    pub switch: Option<LightSwitchPosition>,
+    pub main: Lamp,
+    pub parking: Lamp,
+    pub headlight: Lamp,
+    pub high_beam: Lamp,
+    pub daytime: Lamp,
+    pub position: Lamp,
+    pub fog: Lamp,
+    pub rear_fog: Lamp,
+    pub brake: Lamp,
+    pub reverse: Lamp,
+    pub license_plate: Lamp,
+    /// `DashboardLight` as reported by the vehicle.
+    pub dashboard: bool,
+}
+
+impl LightingState {
+    /// Reads the exterior lights from the telemetry.
+    pub fn from_vehicle(v: &ApiVehicleType) -> Self {
+        let lamps = &v.all_lamps;
+        let intensity = |names: &[&str]| {
+            names
+                .iter()
+                .filter_map(|n| lamps.get(n))
+                .fold(0.0f32, f32::max)
+        };
+        let flag = |s: &str| s.eq_ignore_ascii_case("true");
+
+        Self {
+            switch: v
+                .get_button(LIGHT_SWITCH_BUTTON)
+                .and_then(|b| LightSwitchPosition::from_state(&b.state)),
+            main: Lamp::new(intensity(&["Light MAIN"]), flag(&v.main_light)),
+            parking: Lamp::new(lamps.light_parking, flag(&v.parking_light)),
+            headlight: Lamp::new(lamps.light_headlight, flag(&v.headlight)),
+            high_beam: Lamp::new(lamps.traveller_light, flag(&v.traveller_light)),
+            daytime: Lamp::new(
+                intensity(&["LightDaytime", "LightDaytimeLeft", "LightDaytimeRight"]),
+                flag(&v.daytime_light),
+            ),
+            position: Lamp::new(intensity(&["PositionLights", "LightPositionFront"]), false),
+            fog: Lamp::new(intensity(&["LightFog"]), flag(&v.fog_light)),
+            rear_fog: Lamp::new(intensity(&["LightRearFog"]), flag(&v.rear_fog_light)),
+            brake: Lamp::new(intensity(&["LightBrake"]), false),
+            reverse: Lamp::new(intensity(&["LightReversal"]), false),
+            license_plate: Lamp::new(intensity(&["LightLicensePlate"]), false),
+            dashboard: flag(&v.dashboard_light),
+        }
+    }
+}
+
+/// Returns the events moving the light switch of the vehicle to `to`.
+///
+/// Returns `None` if the vehicle has no light switch, its position is unknown or it
+/// can not be moved to `to` with the "LightSwitchUp" and "LightSwitchDown" actions.
+pub fn light_switch_events(v: &ApiVehicleType, to: LightSwitchPosition) -> Option<Vec<String>> {
+    let button = v.get_button(LIGHT_SWITCH_BUTTON)?;
+    let from = LightSwitchPosition::from_state(&button.state)?;
+    if !button.states.is_empty()
+        && !button
+            .states
+            .iter()
+            .any(|s| LightSwitchPosition::from_state(s) == Some(to))
+    {
+        return None;
+    }
+
+    let action = if to.step() > from.step() {
+        LIGHT_SWITCH_UP
+    } else {
+        LIGHT_SWITCH_DOWN
+    };
+    let steps = to.step().abs_diff(from.step()) as usize;
+    if steps == 0 {
+        return Some(vec![]);
+    }
+    let event = button.actions.iter().find(|a| a.as_str() == action)?;
+    Some(vec![event.clone(); steps])
+}
+
+/// Moves the light switch to `position`.
+pub async fn set_light_switch(
+    config: &RequestConfig,
+    v: &ApiVehicleType,
+    position: LightSwitchPosition,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let events = light_switch_events(v, position)
+        .ok_or("the light switch can not be moved to this position on this vehicle")?;
+    for event in events {
+        send_telemetry_bus_event(config, &event).await?;
+    }
+    Ok(())
+}
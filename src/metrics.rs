@@ -0,0 +1,237 @@
+//! This module renders telemetry snapshots in the Prometheus text exposition format.
+//!
+//! The `the-bus-metrics` binary (feature `metrics`) polls the game and serves the
+//! output of `render_metrics` on `/metrics`.
+
+use crate::api::{ApiVehicleType, ApiWorldType};
+use std::fmt::Write;
+
+/// Renders vehicle and world gauges. Missing snapshots are reported through `thebus_up`.
+pub fn render_metrics(vehicle: Option<&ApiVehicleType>, world: Option<&ApiWorldType>) -> String {
+    let mut out = String::new();
+
+    gauge_header(
+        &mut out,
+        "thebus_up",
+        "1 if the player is in a vehicle and telemetry could be read.",
+    );
+    sample(
+        &mut out,
+        "thebus_up",
+        &[],
+        if vehicle.is_some() { 1.0 } else { 0.0 },
+    );
+
+    if let Some(v) = vehicle {
+        let labels = [
+            ("actor", v.actor_name.as_str()),
+            ("model", v.vehicle_model.as_str()),
+        ];
+
+        let gauges: [(&str, &str, f64); 13] = [
+            (
+                "thebus_vehicle_speed_kmh",
+                "Current speed in km/h.",
+                v.speed.abs() as f64,
+            ),
+            (
+                "thebus_vehicle_allowed_speed_kmh",
+                "Allowed speed in km/h.",
+                v.allowed_speed as f64,
+            ),
+            (
+                "thebus_vehicle_rpm",
+                "Engine revolutions per minute.",
+                v.rpm as f64,
+            ),
+            (
+                "thebus_vehicle_fuel_ratio",
+                "Displayed fuel or battery level (0 to 1).",
+                v.display_fuel as f64,
+            ),
+            (
+                "thebus_vehicle_fuel_current",
+                "Current fuel or battery charge.",
+                v.current_fuel as f64,
+            ),
+            (
+                "thebus_vehicle_fuel_max",
+                "Fuel tank or battery capacity.",
+                v.max_fuel as f64,
+            ),
+            (
+                "thebus_vehicle_ignition",
+                "1 if the ignition is enabled.",
+                flag(&v.ignition_enabled),
+            ),
+            (
+                "thebus_vehicle_engine_started",
+                "1 if the engine is running.",
+                flag(&v.engine_started),
+            ),
+            (
+                "thebus_vehicle_fixing_brake",
+                "1 if the fixing brake is engaged.",
+                flag(&v.fixing_brake),
+            ),
+            (
+                "thebus_vehicle_warning_lights",
+                "1 if the warning lights are on.",
+                flag(&v.warning_lights),
+            ),
+            (
+                "thebus_vehicle_passenger_doors_open",
+                "1 if any passenger door is open.",
+                flag(&v.passenger_doors_open),
+            ),
+            (
+                "thebus_vehicle_seats",
+                "Number of passenger seats.",
+                v.num_seats as f64,
+            ),
+            (
+                "thebus_vehicle_occupied_seats",
+                "Number of occupied passenger seats.",
+                v.num_occupied_seats as f64,
+            ),
+        ];
+
+        for (name, help, value) in gauges {
+            gauge_header(&mut out, name, help);
+            sample(&mut out, name, &labels, value);
+        }
+
+        gauge_header(
+            &mut out,
+            "thebus_vehicle_door_open",
+            "1 if the door is open.",
+        );
+        for door in &v.doors {
+            let door_labels = [labels[0], labels[1], ("door", door.name.as_str())];
+            sample(
+                &mut out,
+                "thebus_vehicle_door_open",
+                &door_labels,
+                flag(&door.open),
+            );
+        }
+
+        gauge_header(
+            &mut out,
+            "thebus_vehicle_door_progress",
+            "Door opening progress (0 to 1).",
+        );
+        for door in &v.doors {
+            let door_labels = [labels[0], labels[1], ("door", door.name.as_str())];
+            let progress = door.progress.parse::<f64>().unwrap_or(0.0);
+            sample(
+                &mut out,
+                "thebus_vehicle_door_progress",
+                &door_labels,
+                progress,
+            );
+        }
+
+        gauge_header(
+            &mut out,
+            "thebus_vehicle_lamp",
+            "Lamp intensity by lamp name.",
+        );
+        for (lamp, value) in v.all_lamps.all() {
+            let lamp_labels = [labels[0], labels[1], ("lamp", lamp.as_str())];
+            sample(&mut out, "thebus_vehicle_lamp", &lamp_labels, value as f64);
+        }
+    }
+
+    if let Some(w) = world {
+        let labels = [("level", w.level_name.as_str())];
+
+        let gauges: [(&str, &str, f64); 9] = [
+            (
+                "thebus_world_time_factor",
+                "Time acceleration factor.",
+                w.time_factor as f64,
+            ),
+            (
+                "thebus_world_temperature_celsius",
+                "Outside temperature in degrees Celsius.",
+                w.temperature as f64,
+            ),
+            (
+                "thebus_world_wetness",
+                "Wetness of the roads (0 to 1).",
+                w.wetness as f64,
+            ),
+            (
+                "thebus_world_rain_intensity",
+                "Rain intensity (0 to 1).",
+                w.rain_intensity as f64,
+            ),
+            (
+                "thebus_world_snow_intensity",
+                "Snow intensity (0 to 1).",
+                w.snow_intensity as f64,
+            ),
+            (
+                "thebus_world_snow_pack",
+                "Snow cover on the ground (0 to 1).",
+                w.snow_pack as f64,
+            ),
+            (
+                "thebus_world_cloud_intensity",
+                "Cloud intensity (0 to 1).",
+                w.cloud_intensity as f64,
+            ),
+            (
+                "thebus_world_fog_intensity",
+                "Fog intensity (0 to 1).",
+                w.nebular_intensity as f64,
+            ),
+            (
+                "thebus_world_wind_intensity",
+                "Wind intensity (0 to 1).",
+                w.wind_intensity as f64,
+            ),
+        ];
+
+        for (name, help, value) in gauges {
+            gauge_header(&mut out, name, help);
+            sample(&mut out, name, &labels, value);
+        }
+    }
+
+    out
+}
+
+fn flag(value: &str) -> f64 {
+    if value.eq_ignore_ascii_case("true") {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+fn gauge_header(out: &mut String, name: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+}
+
+fn sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: f64) {
+    out.push_str(name);
+    if !labels.is_empty() {
+        let labels: Vec<String> = labels
+            .iter()
+            .map(|(k, v)| format!("{}=\"{}\"", k, escape_label(v)))
+            .collect();
+        let _ = write!(out, "{{{}}}", labels.join(","));
+    }
+    let _ = writeln!(out, " {}", value);
+}
+
+/// Escapes a label value as required by the text exposition format.
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,263 @@
+//! This module bridges telemetry to an MQTT broker (feature `mqtt`).
+//!
+//! Published topics (all retained, only sent on change):
+//! - `<prefix>/state/<field>`: fields of the komsi `VehicleState`
+//! - `<prefix>/telemetry/vehicle`: vehicle telemetry as received from the API
+//! - `<prefix>/telemetry/world`: world telemetry as received from the API
+//!
+//! When the player leaves the vehicle (or the world can not be read), the retained
+//! topics are cleared by publishing empty retained messages. The bridge reconnects
+//! after a lost broker connection and publishes all topics again.
+//!
+//! Messages on `<prefix>/cmd` are forwarded to `send_telemetry_bus_cmd`,
+//! the payload being the command path (e.g. `sendeventpress?event=Horn`).
+
+use crate::api::{ApiVehicleType, RequestConfig, get_telemetry_data, send_telemetry_bus_cmd};
+use crate::api2vehicle::get_vehicle_state_from_api;
+use komsi::vehicle::VehicleState;
+use rumqttc::{AsyncClient, ClientError, Event, MqttOptions, Packet, QoS};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Delay before polling the broker connection again after an error.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Configuration of the MQTT bridge.
+pub struct MqttBridgeConfig {
+    /// Host of the MQTT broker (default: "127.0.0.1").
+    pub broker_host: String,
+    /// Port of the MQTT broker (default: 1883).
+    pub broker_port: u16,
+    /// Client id used at the broker (default: "the-bus-telemetry").
+    pub client_id: String,
+    /// Prefix of all topics (default: "thebus").
+    pub prefix: String,
+    /// Interval between telemetry polls (default: 250ms).
+    pub interval: Duration,
+}
+
+impl MqttBridgeConfig {
+    /// Creates a new `MqttBridgeConfig` with default values.
+    pub fn new() -> Self {
+        Self {
+            broker_host: "127.0.0.1".to_string(),
+            broker_port: 1883,
+            client_id: "the-bus-telemetry".to_string(),
+            prefix: "thebus".to_string(),
+            interval: Duration::from_millis(250),
+        }
+    }
+
+    /// Sets the broker host.
+    pub fn broker_host(mut self, broker_host: String) -> Self {
+        self.broker_host = broker_host;
+        self
+    }
+
+    /// Sets the broker port.
+    pub fn broker_port(mut self, broker_port: u16) -> Self {
+        self.broker_port = broker_port;
+        self
+    }
+
+    /// Sets the client id.
+    pub fn client_id(mut self, client_id: String) -> Self {
+        self.client_id = client_id;
+        self
+    }
+
+    /// Sets the topic prefix.
+    pub fn prefix(mut self, prefix: String) -> Self {
+        self.prefix = prefix;
+        self
+    }
+
+    /// Sets the poll interval.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+}
+
+impl Default for MqttBridgeConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the topics and payloads for all fields of a `VehicleState`.
+pub fn vehicle_state_topics(prefix: &str, s: &VehicleState) -> Vec<(String, String)> {
+    let fields = [
+        ("ignition", s.ignition.to_string()),
+        ("engine", s.engine.to_string()),
+        ("doors", s.doors.to_string()),
+        ("fixing_brake", s.fixing_brake.to_string()),
+        ("indicator", s.indicator.to_string()),
+        ("gear_selector", s.gear_selector.to_string()),
+        ("speed", s.speed.to_string()),
+        ("maxspeed", s.maxspeed.to_string()),
+        ("fuel", s.fuel.to_string()),
+        ("lights_warning", s.lights_warning.to_string()),
+        ("lights_main", s.lights_main.to_string()),
+        ("lights_high_beam", s.lights_high_beam.to_string()),
+        ("lights_front_door", s.lights_front_door.to_string()),
+        ("lights_second_door", s.lights_second_door.to_string()),
+        ("lights_third_door", s.lights_third_door.to_string()),
+        ("lights_fourth_door", s.lights_fourth_door.to_string()),
+        ("lights_stop_request", s.lights_stop_request.to_string()),
+        ("lights_stop_brake", s.lights_stop_brake.to_string()),
+        ("door_clearance", s.door_clearance.to_string()),
+    ];
+
+    fields
+        .into_iter()
+        .map(|(name, value)| (format!("{}/state/{}", prefix, name), value))
+        .collect()
+}
+
+/// Returns the messages for a vehicle payload as received from the API: the payload
+/// itself and, if it parses, the fields of the komsi `VehicleState`.
+pub fn vehicle_messages(prefix: &str, raw: &Value) -> Vec<(String, String)> {
+    let mut messages = vec![(format!("{}/telemetry/vehicle", prefix), raw.to_string())];
+    if let Ok(vehicle) = serde_json::from_value::<ApiVehicleType>(raw.clone()) {
+        let state = get_vehicle_state_from_api(vehicle);
+        messages.extend(vehicle_state_topics(prefix, &state));
+    }
+    messages
+}
+
+/// Publishes retained messages on change and clears them again.
+pub struct MqttPublisher {
+    client: AsyncClient,
+    /// last published payload per topic, to publish changes only
+    published: HashMap<String, String>,
+}
+
+impl MqttPublisher {
+    /// Creates a publisher sending through `client`.
+    pub fn new(client: AsyncClient) -> Self {
+        Self {
+            client,
+            published: HashMap::new(),
+        }
+    }
+
+    /// Publishes the messages whose payload changed since the last publish, retained.
+    ///
+    /// Messages are queued without waiting; messages that don't fit into the queue are
+    /// returned as error and sent again with the next call.
+    pub fn publish(&mut self, messages: Vec<(String, String)>) -> Result<(), ClientError> {
+        for (topic, payload) in messages {
+            if self.published.get(&topic) == Some(&payload) {
+                continue;
+            }
+            self.client
+                .try_publish(topic.clone(), QoS::AtLeastOnce, true, payload.clone())?;
+            self.published.insert(topic, payload);
+        }
+        Ok(())
+    }
+
+    /// Clears the retained topics starting with `topic_prefix` by publishing empty
+    /// retained messages.
+    pub fn clear(&mut self, topic_prefix: &str) -> Result<(), ClientError> {
+        let topics: Vec<String> = self
+            .published
+            .keys()
+            .filter(|t| t.starts_with(topic_prefix))
+            .cloned()
+            .collect();
+        for topic in topics {
+            self.client
+                .try_publish(topic.clone(), QoS::AtLeastOnce, true, Vec::new())?;
+            self.published.remove(&topic);
+        }
+        Ok(())
+    }
+
+    /// Forgets what was published, so that all topics are sent again.
+    pub fn reset(&mut self) {
+        self.published.clear();
+    }
+}
+
+/// Runs the bridge. Broker connection errors are logged and the connection is retried.
+pub async fn run_mqtt_bridge(
+    config: &RequestConfig,
+    bridge: &MqttBridgeConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut options = MqttOptions::new(
+        bridge.client_id.clone(),
+        bridge.broker_host.clone(),
+        bridge.broker_port,
+    );
+    options.set_keep_alive(Duration::from_secs(5));
+
+    let (client, mut eventloop) = AsyncClient::new(options, 64);
+    let cmd_topic = format!("{}/cmd", bridge.prefix);
+    let vehicle_path = format!("vehicles/{}", config.vehicle_name);
+    let vehicle_topics = [
+        format!("{}/telemetry/vehicle", bridge.prefix),
+        format!("{}/state/", bridge.prefix),
+    ];
+    let world_topic = format!("{}/telemetry/world", bridge.prefix);
+
+    let mut publisher = MqttPublisher::new(client.clone());
+    let mut connected = false;
+    let mut ticker = tokio::time::interval(bridge.interval);
+
+    loop {
+        tokio::select! {
+            event = eventloop.poll() => match event {
+                Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                    connected = true;
+                    // the broker may have lost subscriptions and retained messages
+                    if let Err(e) = client.try_subscribe(cmd_topic.clone(), QoS::AtLeastOnce) {
+                        eprintln!("Failed to subscribe to \"{}\": {}", cmd_topic, e);
+                    }
+                    publisher.reset();
+                }
+                Ok(Event::Incoming(Packet::Publish(p))) if p.topic == cmd_topic => {
+                    let cmd = String::from_utf8_lossy(&p.payload).to_string();
+                    if let Err(e) = send_telemetry_bus_cmd(config, &cmd).await {
+                        eprintln!("Failed to send command \"{}\": {}", cmd, e);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    if connected {
+                        eprintln!("MQTT connection lost: {}", e);
+                    }
+                    connected = false;
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                }
+            },
+            _ = ticker.tick(), if connected => {
+                let vehicle = get_telemetry_data(config, &vehicle_path).await;
+                let world = get_telemetry_data(config, "world").await;
+
+                let result = match vehicle {
+                    Ok(raw) if in_vehicle(&raw) => {
+                        publisher.publish(vehicle_messages(&bridge.prefix, &raw))
+                    }
+                    _ => vehicle_topics.iter().try_for_each(|t| publisher.clear(t)),
+                }
+                .and_then(|_| match world {
+                    Ok(raw) => publisher.publish(vec![(world_topic.clone(), raw.to_string())]),
+                    Err(_) => publisher.clear(&world_topic),
+                });
+                if let Err(e) = result {
+                    eprintln!("Failed to publish telemetry: {}", e);
+                }
+            }
+        }
+    }
+}
+
+/// Returns true if the vehicle payload names a vehicle, i.e. the player is in one.
+fn in_vehicle(raw: &Value) -> bool {
+    raw.get("ActorName")
+        .and_then(Value::as_str)
+        .is_some_and(|name| !name.is_empty())
+}
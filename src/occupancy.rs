@@ -0,0 +1,86 @@
+//! This module tracks passenger occupancy from `NumSeats` and `NumOccupiedSeats`.
+//!
+//! Occupancy changes while passenger doors are open are attributed to that door opening:
+//! increases count as boardings, decreases as alightings.
+
+use crate::api::ApiVehicleType;
+use serde::Serialize;
+
+/// Passenger changes during one door opening.
+#[derive(Serialize, Debug, PartialEq, Default, Clone)]
+pub struct StopOccupancy {
+    /// Number of the door opening, counted from 0.
+    pub index: usize,
+    pub boardings: u32,
+    pub alightings: u32,
+    /// Occupied seats when the doors closed.
+    pub load: u32,
+}
+
+/// Summary of the occupancy so far.
+#[derive(Serialize, Debug, PartialEq, Default, Clone)]
+pub struct OccupancyReport {
+    /// Number of passenger seats.
+    pub seats: u32,
+    /// Currently occupied seats.
+    pub load: u32,
+    /// Highest number of occupied seats.
+    pub peak_load: u32,
+    pub boardings: u32,
+    pub alightings: u32,
+    /// Changes per door opening, in order.
+    pub stops: Vec<StopOccupancy>,
+}
+
+/// Stateful occupancy tracker fed by snapshots.
+#[derive(Debug, Default)]
+pub struct OccupancyTracker {
+    report: OccupancyReport,
+    last: Option<u32>,
+    current: Option<StopOccupancy>,
+}
+
+impl OccupancyTracker {
+    /// Creates a new tracker.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a snapshot.
+    pub fn update(&mut self, v: &ApiVehicleType) {
+        let load = v.num_occupied_seats;
+        let doors_open = v.passenger_doors_open.eq_ignore_ascii_case("true");
+
+        if doors_open && self.current.is_none() {
+            self.current = Some(StopOccupancy {
+                index: self.report.stops.len(),
+                ..Default::default()
+            });
+        }
+
+        if let (Some(last), Some(stop)) = (self.last, self.current.as_mut()) {
+            if load > last {
+                stop.boardings += load - last;
+                self.report.boardings += load - last;
+            } else {
+                stop.alightings += last - load;
+                self.report.alightings += last - load;
+            }
+        }
+
+        if !doors_open && let Some(mut stop) = self.current.take() {
+            stop.load = load;
+            self.report.stops.push(stop);
+        }
+
+        self.report.seats = v.num_seats;
+        self.report.load = load;
+        self.report.peak_load = self.report.peak_load.max(load);
+        self.last = Some(load);
+    }
+
+    /// Returns the occupancy report so far.
+    pub fn report(&self) -> OccupancyReport {
+        self.report.clone()
+    }
+}
@@ -0,0 +1,307 @@
+//! This module handles mapping profiles that extend or override the built-in mapping
+//! used by `api2vehicle` (lamp names, button names and button state tables).
+//!
+//! A profile can be loaded at runtime from a TOML or JSON file, e.g.:
+//!
+//! ```toml
+//! [lamps]
+//! "Light Parking" = ["LightParking2"]
+//!
+//! [buttons]
+//! "Gear Selector" = ["Gear Lever"]
+//!
+//! [states."Gear Selector"]
+//! D = 1
+//! N = 2
+//! R = 3
+//! ```
+//!
+//! Only the "Gear Selector" state table is mapped, other state tables are rejected.
+
+use crate::api::{ApiVehicleType, canonical_lamp_name};
+use serde::Deserialize;
+use serde::de::{self, Deserializer};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Canonical names of the typed lamps in `ApiLamps`, together with the built-in aliases.
+pub const BUILTIN_LAMPS: &[(&str, &[&str])] = &[
+    ("LightHeadlight", &["LightHeadlight1", "Light Headlight"]),
+    ("Light Parking", &["LightParking1", "LightParking"]),
+    ("Light MAIN", &["Light Main", "LightMain"]),
+    ("LightTraveling", &["LightTraveling1", "Light Travelling"]),
+    ("Door Button 1", &["ButtonLight Door 1"]),
+    ("Door Button 2", &["ButtonLight Door 2", "LightDoorMiddle"]),
+    ("Door Button 3", &["ButtonLight Door 3"]),
+    ("Door Button 4", &["ButtonLight Door 4"]),
+    ("LED StopRequest", &["DB Stop Request", "TachoStopRequest"]),
+    ("ButtonLight BusStopBrake", &["LED Stop Brake"]),
+    ("ButtonLight DoorClearance", &["DoorClearanceButton"]),
+];
+
+/// Name of the button holding the gear selector state.
+pub const GEAR_SELECTOR: &str = "Gear Selector";
+
+/// Error while loading a mapping profile, pointing at the offending line.
+#[derive(Debug, PartialEq)]
+pub struct ProfileError {
+    /// Line in the profile source (1-based, 0 if unknown).
+    pub line: usize,
+    /// Column in the profile source (1-based, 0 if unknown).
+    pub column: usize,
+    /// Description of the problem.
+    pub message: String,
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for ProfileError {}
+
+/// Raw layout of a profile file.
+///
+/// Values are validated while deserializing, so errors carry the position reported by
+/// the TOML or JSON parser.
+#[derive(Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+struct ProfileFile {
+    #[serde(default)]
+    lamps: HashMap<String, Aliases>,
+    #[serde(default)]
+    buttons: HashMap<String, Aliases>,
+    #[serde(default)]
+    states: StateTables,
+}
+
+/// State tables of a profile file. Only the gear selector is mapped by `api2vehicle`,
+/// so any other table is rejected.
+#[derive(Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+struct StateTables {
+    #[serde(rename = "Gear Selector", default)]
+    gear_selector: HashMap<String, GearSelectorValue>,
+}
+
+/// Non-empty list of alternative names.
+#[derive(Debug)]
+struct Aliases(Vec<String>);
+
+impl<'de> Deserialize<'de> for Aliases {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let names = Vec::<String>::deserialize(deserializer)?;
+        if names.is_empty() {
+            return Err(de::Error::custom("no alternative names given"));
+        }
+        Ok(Aliases(names))
+    }
+}
+
+/// Gear selector value of a state.
+#[derive(Debug)]
+struct GearSelectorValue(u8);
+
+impl<'de> Deserialize<'de> for GearSelectorValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = u8::deserialize(deserializer)?;
+        if !(1..=3).contains(&value) {
+            return Err(de::Error::custom(format!(
+                "gear selector value must be 1 (drive), 2 (neutral) or 3 (reverse), got {}",
+                value
+            )));
+        }
+        Ok(GearSelectorValue(value))
+    }
+}
+
+/// Mapping of payload names to the names and values used by `api2vehicle`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MappingProfile {
+    /// Canonical lamp name -> additional names found in `AllLamps`.
+    pub lamps: HashMap<String, Vec<String>>,
+    /// Canonical button name -> additional names found in `Buttons`.
+    pub buttons: HashMap<String, Vec<String>>,
+    /// Canonical button name -> (button state -> komsi value).
+    pub states: HashMap<String, HashMap<String, u8>>,
+}
+
+impl Default for MappingProfile {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl MappingProfile {
+    /// Returns the mapping built into the crate.
+    ///
+    /// Lamp aliases are already handled by `ApiLamps`, so only the state tables are filled.
+    pub fn builtin() -> Self {
+        let gear_selector = HashMap::from([
+            ("Drive".to_string(), 1),
+            ("Neutral".to_string(), 2),
+            ("Reverse".to_string(), 3),
+        ]);
+
+        Self {
+            lamps: HashMap::new(),
+            buttons: HashMap::new(),
+            states: HashMap::from([(GEAR_SELECTOR.to_string(), gear_selector)]),
+        }
+    }
+
+    /// Parses a TOML profile and merges it on top of the built-in mapping.
+    pub fn from_toml_str(source: &str) -> Result<Self, ProfileError> {
+        let file: ProfileFile = toml::from_str(source).map_err(|e| {
+            let (line, column) = e
+                .span()
+                .map(|span| line_column(source, span.start))
+                .unwrap_or((0, 0));
+            ProfileError {
+                line,
+                column,
+                message: e.message().to_string(),
+            }
+        })?;
+
+        Ok(Self::from_profile_file(file))
+    }
+
+    /// Parses a JSON profile and merges it on top of the built-in mapping.
+    pub fn from_json_str(source: &str) -> Result<Self, ProfileError> {
+        let file: ProfileFile = serde_json::from_str(source).map_err(|e| ProfileError {
+            line: e.line(),
+            column: e.column(),
+            message: e.to_string(),
+        })?;
+
+        Ok(Self::from_profile_file(file))
+    }
+
+    /// Loads a profile from a file, choosing the format by extension (`.json`, otherwise TOML).
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)?;
+
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+
+        let profile = if is_json {
+            Self::from_json_str(&source)?
+        } else {
+            Self::from_toml_str(&source)?
+        };
+        Ok(profile)
+    }
+
+    fn from_profile_file(file: ProfileFile) -> Self {
+        let aliases = |table: HashMap<String, Aliases>| {
+            table
+                .into_iter()
+                .map(|(name, aliases)| (name, aliases.0))
+                .collect()
+        };
+        let gear_selector = file
+            .states
+            .gear_selector
+            .into_iter()
+            .map(|(state, value)| (state, value.0))
+            .collect();
+
+        // lamps outside of BUILTIN_LAMPS are kept in ApiLamps::other, so any name is fine
+        let mut profile = Self::builtin();
+        profile.merge(MappingProfile {
+            lamps: aliases(file.lamps),
+            buttons: aliases(file.buttons),
+            states: HashMap::from([(GEAR_SELECTOR.to_string(), gear_selector)]),
+        });
+        profile
+    }
+
+    /// Merges another profile into this one. Aliases are added, state values are overridden.
+    pub fn merge(&mut self, other: MappingProfile) {
+        for (lamp, names) in other.lamps {
+            self.lamps.entry(lamp).or_default().extend(names);
+        }
+        for (button, names) in other.buttons {
+            self.buttons.entry(button).or_default().extend(names);
+        }
+        for (button, table) in other.states {
+            self.states.entry(button).or_default().extend(table);
+        }
+    }
+
+    /// Returns the mapped value of a button state, if the profile knows it.
+    pub fn state_value(&self, button: &str, state: &str) -> Option<u8> {
+        self.states.get(button).and_then(|t| t.get(state)).copied()
+    }
+
+    /// Renames lamps and buttons in a raw vehicle payload to their canonical names.
+    ///
+    /// A lamp found under an alias overrides a lamp already present under the canonical name.
+    /// Profile lamps naming a typed lamp by one of its built-in aliases (e.g. "LightParking")
+    /// are stored under the name of `BUILTIN_LAMPS` (e.g. "Light Parking").
+    pub fn apply(&self, data: &mut serde_json::Value) {
+        if let Some(lamps) = data.get_mut("AllLamps").and_then(|v| v.as_object_mut()) {
+            for (lamp, aliases) in &self.lamps {
+                let builtin = BUILTIN_LAMPS
+                    .iter()
+                    .find(|(name, _)| canonical_lamp_name(name) == canonical_lamp_name(lamp));
+                let canonical = builtin.map_or(lamp.as_str(), |(name, _)| *name);
+                for alias in aliases {
+                    if let Some(value) = lamps.remove(alias) {
+                        // drop built-in variants, ApiLamps rejects the same lamp twice
+                        if let Some((_, variants)) = builtin {
+                            for name in variants.iter() {
+                                lamps.remove(*name);
+                            }
+                        }
+                        lamps.insert(canonical.to_string(), value);
+                    }
+                }
+            }
+        }
+
+        if let Some(buttons) = data.get_mut("Buttons").and_then(|v| v.as_array_mut()) {
+            for button in buttons.iter_mut() {
+                let Some(name) = button.get("Name").and_then(|n| n.as_str()) else {
+                    continue;
+                };
+                let canonical = self
+                    .buttons
+                    .iter()
+                    .find(|(_, aliases)| aliases.iter().any(|a| a == name))
+                    .map(|(canonical, _)| canonical.clone());
+
+                if let Some(canonical) = canonical {
+                    button["Name"] = serde_json::Value::String(canonical);
+                }
+            }
+        }
+    }
+
+    /// Applies the profile to a raw vehicle payload and deserializes it.
+    pub fn parse_vehicle(
+        &self,
+        mut data: serde_json::Value,
+    ) -> Result<ApiVehicleType, serde_json::Error> {
+        self.apply(&mut data);
+        serde_json::from_value(data)
+    }
+}
+
+/// Converts a byte offset into a 1-based line and column.
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset.min(source.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+    (line, column)
+}
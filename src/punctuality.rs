@@ -0,0 +1,271 @@
+//! This module measures dwell times at stops and compares departures with a timetable.
+//!
+//! A stop visit starts when `IsAtStop` becomes true and ends when it becomes false again.
+//! Only visits during which passenger doors were opened count as served stops. Times are
+//! in-game times taken from the world `DateTime`.
+//!
+//! The telemetry does not name the stop, so visits are matched to the timetable in order.
+
+use crate::api::{ApiVehicleType, ApiWorldType};
+use serde::Serialize;
+use std::fmt;
+use std::path::Path;
+
+/// Error returned when a timetable can not be parsed.
+#[derive(Debug, PartialEq)]
+pub struct TimetableError {
+    /// Line of the error (1-based).
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for TimetableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for TimetableError {}
+
+/// A planned departure.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TimetableEntry {
+    pub stop: String,
+    /// Planned departure in seconds since midnight.
+    pub departure: u32,
+}
+
+/// Ordered list of planned departures.
+#[derive(Debug, PartialEq, Default, Clone)]
+pub struct Timetable {
+    pub entries: Vec<TimetableEntry>,
+}
+
+impl Timetable {
+    /// Parses a CSV with the columns stop name and departure time ("HH:MM" or "HH:MM:SS").
+    ///
+    /// A header line, empty lines and lines starting with '#' are skipped.
+    /// Stop names containing commas have to be quoted.
+    pub fn from_csv_str(source: &str) -> Result<Self, TimetableError> {
+        let mut entries = Vec::new();
+
+        for (i, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message: &str| TimetableError {
+                line: i + 1,
+                message: message.to_string(),
+            };
+
+            let (stop, time) = split_csv_line(line).ok_or_else(|| error("expected two columns"))?;
+            match parse_time_of_day(&time) {
+                Some(departure) => entries.push(TimetableEntry { stop, departure }),
+                // header line
+                None if entries.is_empty() && i == 0 => {}
+                None => return Err(error(&format!("invalid time \"{}\"", time))),
+            }
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Reads a timetable CSV file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        let source = std::fs::read_to_string(path)?;
+        Ok(Self::from_csv_str(&source)?)
+    }
+}
+
+/// A served stop.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct StopVisit {
+    /// Stop name from the timetable, if matched.
+    pub stop: Option<String>,
+    /// Arrival at the stop ("HH:MM:SS").
+    pub arrival: String,
+    /// Departure from the stop ("HH:MM:SS"), if the bus left already.
+    pub departure: Option<String>,
+    /// Time from the first door opening to the last door closing, in seconds.
+    pub dwell_s: i64,
+    /// Planned departure ("HH:MM:SS"), if matched.
+    pub planned: Option<String>,
+    /// Departure (or arrival, while still at the stop) minus planned departure, in seconds.
+    pub delay_s: Option<i64>,
+}
+
+/// Stateful stop and punctuality tracker.
+#[derive(Debug, Default)]
+pub struct PunctualityTracker {
+    timetable: Timetable,
+    visits: Vec<StopVisit>,
+    current: Option<Visit>,
+}
+
+#[derive(Debug)]
+struct Visit {
+    arrival: i64,
+    doors_opened: Option<i64>,
+    doors_closed: Option<i64>,
+    doors_open: bool,
+}
+
+impl PunctualityTracker {
+    /// Creates a new tracker without timetable.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the timetable the visits are matched against.
+    pub fn timetable(mut self, timetable: Timetable) -> Self {
+        self.timetable = timetable;
+        self
+    }
+
+    /// Adds a vehicle snapshot taken at the in-game time of the world snapshot.
+    pub fn update(&mut self, v: &ApiVehicleType, world: &ApiWorldType) {
+        let Some(now) = parse_game_time(&world.date_time) else {
+            return;
+        };
+        let at_stop = v.is_at_stop.eq_ignore_ascii_case("true");
+        let doors_open = v.passenger_doors_open.eq_ignore_ascii_case("true");
+
+        match (self.current.as_mut(), at_stop) {
+            (None, true) => {
+                self.current = Some(Visit {
+                    arrival: now,
+                    doors_opened: doors_open.then_some(now),
+                    doors_closed: None,
+                    doors_open,
+                });
+            }
+            (Some(visit), true) => {
+                if doors_open && !visit.doors_open {
+                    visit.doors_opened.get_or_insert(now);
+                    visit.doors_closed = None;
+                }
+                if !doors_open && visit.doors_open {
+                    visit.doors_closed = Some(now);
+                }
+                visit.doors_open = doors_open;
+            }
+            (Some(_), false) => {
+                if let Some(visit) = self.current.take() {
+                    self.finish(visit, Some(now));
+                }
+            }
+            (None, false) => {}
+        }
+    }
+
+    /// Returns all served stops, including the current one.
+    pub fn visits(&self) -> Vec<StopVisit> {
+        let mut visits = self.visits.clone();
+        if let Some(visit) = &self.current
+            && visit.doors_opened.is_some()
+        {
+            visits.push(self.stop_visit(visit, None, visits.len()));
+        }
+        visits
+    }
+
+    fn finish(&mut self, visit: Visit, departure: Option<i64>) {
+        // the bus only passed the stop
+        if visit.doors_opened.is_none() {
+            return;
+        }
+        let stop_visit = self.stop_visit(&visit, departure, self.visits.len());
+        self.visits.push(stop_visit);
+    }
+
+    fn stop_visit(&self, visit: &Visit, departure: Option<i64>, index: usize) -> StopVisit {
+        let dwell_s = match (visit.doors_opened, visit.doors_closed.or(departure)) {
+            (Some(opened), Some(closed)) if closed >= opened => closed - opened,
+            _ => 0,
+        };
+        let entry = self.timetable.entries.get(index);
+        let actual = departure.unwrap_or(visit.arrival);
+
+        StopVisit {
+            stop: entry.map(|e| e.stop.clone()),
+            arrival: format_time_of_day(visit.arrival),
+            departure: departure.map(format_time_of_day),
+            dwell_s,
+            planned: entry.map(|e| format_time_of_day(e.departure as i64)),
+            delay_s: entry.map(|e| {
+                // times of day wrap at midnight, so take the closest difference
+                let diff = actual.rem_euclid(86400) - e.departure as i64;
+                (diff + 43200).rem_euclid(86400) - 43200
+            }),
+        }
+    }
+}
+
+/// Parses an in-game `DateTime` ("2026-01-01T09:43:48") into seconds since 1970-01-01.
+pub fn parse_game_time(date_time: &str) -> Option<i64> {
+    let (date, time) = date_time.split_once('T')?;
+    let mut d = date.splitn(3, '-').map(|p| p.parse::<i64>().ok());
+    let (year, month, day) = (d.next()??, d.next()??, d.next()??);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let seconds = parse_time_of_day(time.split('.').next()?)? as i64;
+
+    // days from civil date, proleptic Gregorian calendar
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+
+    Some(days * 86400 + seconds)
+}
+
+/// Parses "HH:MM" or "HH:MM:SS" into seconds since midnight.
+fn parse_time_of_day(time: &str) -> Option<u32> {
+    let mut parts = time.trim().split(':');
+    let hour: u32 = parts.next()?.parse().ok()?;
+    let min: u32 = parts.next()?.parse().ok()?;
+    let sec: u32 = match parts.next() {
+        Some(s) => s.parse().ok()?,
+        None => 0,
+    };
+    if parts.next().is_some() || hour > 23 || min > 59 || sec > 59 {
+        return None;
+    }
+    Some(hour * 3600 + min * 60 + sec)
+}
+
+fn format_time_of_day(seconds: i64) -> String {
+    let s = seconds.rem_euclid(86400);
+    format!("{:02}:{:02}:{:02}", s / 3600, s / 60 % 60, s % 60)
+}
+
+/// Splits a CSV line into the first two columns, honouring double quotes.
+fn split_csv_line(line: &str) -> Option<(String, String)> {
+    let mut columns = Vec::new();
+    let mut column = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                column.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => columns.push(std::mem::take(&mut column)),
+            _ => column.push(c),
+        }
+    }
+    columns.push(column);
+
+    if columns.len() < 2 {
+        return None;
+    }
+    let mut columns = columns.into_iter().map(|c| c.trim().to_string());
+    Some((columns.next()?, columns.next()?))
+}
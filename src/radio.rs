@@ -0,0 +1,149 @@
+//! This module reads the radio state and drives the driver radio keypad (`Radio_*` buttons).
+//!
+//! The keypad buttons have no actions (`Actions` is `["None"]`), so keys are pressed by
+//! setting the button state to "true" and back to "false".
+
+use crate::api::{ApiVehicleType, RequestConfig, set_telemetry_bus_button};
+
+/// Prefix of all radio keypad buttons.
+const RADIO_PREFIX: &str = "Radio_";
+
+/// A key of the driver radio keypad.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RadioKey {
+    /// Digit key 0 to 9.
+    Digit(u8),
+    Send,
+    End,
+    Clear,
+    Group,
+    Ext,
+    Menu,
+    Dir,
+    Val,
+    Next,
+    Flash,
+    Multi,
+    Mem,
+    OnOff,
+    Emergency,
+    VolumePlus,
+    VolumeMinus,
+}
+
+/// Named keys and their button name suffix.
+const NAMED_KEYS: &[(RadioKey, &str)] = &[
+    (RadioKey::Send, "SEND"),
+    (RadioKey::End, "END"),
+    (RadioKey::Clear, "CLR"),
+    (RadioKey::Group, "GRP"),
+    (RadioKey::Ext, "EXT"),
+    (RadioKey::Menu, "MENU"),
+    (RadioKey::Dir, "DIR"),
+    (RadioKey::Val, "VAL"),
+    (RadioKey::Next, "NEXT"),
+    (RadioKey::Flash, "FLASH"),
+    (RadioKey::Multi, "MULTI"),
+    (RadioKey::Mem, "MEM"),
+    (RadioKey::OnOff, "OnOff"),
+    (RadioKey::Emergency, "Emergency"),
+    (RadioKey::VolumePlus, "VOL_PLUS"),
+    (RadioKey::VolumeMinus, "VOL_MINUS"),
+];
+
+impl RadioKey {
+    /// Returns the name of the button of this key (e.g. "Radio_4", "Radio_SEND").
+    pub fn button_name(&self) -> String {
+        match self {
+            RadioKey::Digit(d) => format!("{}{}", RADIO_PREFIX, d),
+            key => {
+                let suffix = NAMED_KEYS
+                    .iter()
+                    .find(|(k, _)| k == key)
+                    .map(|(_, s)| *s)
+                    .unwrap_or_default();
+                format!("{}{}", RADIO_PREFIX, suffix)
+            }
+        }
+    }
+
+    /// Returns the key of a button name, if it is a radio key.
+    pub fn from_button_name(name: &str) -> Option<Self> {
+        let suffix = name.strip_prefix(RADIO_PREFIX)?;
+        if let Ok(d) = suffix.parse::<u8>()
+            && d <= 9
+            && suffix.len() == 1
+        {
+            return Some(RadioKey::Digit(d));
+        }
+        NAMED_KEYS
+            .iter()
+            .find(|(_, s)| *s == suffix)
+            .map(|(k, _)| *k)
+    }
+}
+
+/// Radio state of the vehicle.
+#[derive(Debug, PartialEq, Default, Clone)]
+pub struct RadioState {
+    pub playing: bool,
+    pub channel: String,
+    pub volume: f32,
+    /// Keypad keys of the vehicle; empty if it has no driver radio.
+    pub keys: Vec<RadioKey>,
+}
+
+impl RadioState {
+    /// Reads the radio state from the telemetry.
+    pub fn from_vehicle(v: &ApiVehicleType) -> Self {
+        Self {
+            playing: v.is_radio_playing.eq_ignore_ascii_case("true"),
+            channel: v.active_radio_channel.clone(),
+            volume: v.radio_volume,
+            keys: v
+                .buttons
+                .iter()
+                .filter_map(|b| RadioKey::from_button_name(&b.name))
+                .collect(),
+        }
+    }
+
+    /// Returns true if the vehicle has a driver radio keypad.
+    pub fn has_keypad(&self) -> bool {
+        !self.keys.is_empty()
+    }
+}
+
+/// Returns the keys dialling `number` followed by SEND, e.g. "42" -> 4, 2, SEND.
+///
+/// Returns `None` if `number` is empty or contains anything but digits.
+pub fn dial_keys(number: &str) -> Option<Vec<RadioKey>> {
+    if number.is_empty() {
+        return None;
+    }
+    let mut keys = number
+        .chars()
+        .map(|c| c.to_digit(10).map(|d| RadioKey::Digit(d as u8)))
+        .collect::<Option<Vec<_>>>()?;
+    keys.push(RadioKey::Send);
+    Some(keys)
+}
+
+/// Presses and releases a keypad key.
+pub async fn press_radio_key(
+    config: &RequestConfig,
+    key: RadioKey,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let button = key.button_name();
+    set_telemetry_bus_button(config, &button, "true").await?;
+    set_telemetry_bus_button(config, &button, "false").await
+}
+
+/// Dials `number` and sends the call.
+pub async fn dial(config: &RequestConfig, number: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let keys = dial_keys(number).ok_or_else(|| format!("invalid radio number \"{}\"", number))?;
+    for key in keys {
+        press_radio_key(config, key).await?;
+    }
+    Ok(())
+}
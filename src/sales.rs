@@ -0,0 +1,172 @@
+//! This module records ticket revenue and operates the cash buttons of the fare panel.
+//!
+//! The coin buttons ("5 Cent" to "8 Euro") put change into the tray, "Cash Money" takes
+//! the money handed over by the passenger and "BoardComputer" selects the ticket printer.
+
+use crate::api::{ApiSale, ApiVehicleType, RequestConfig, send_telemetry_bus_event};
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// Coin buttons with their value in cents and the event they trigger.
+pub const COIN_BUTTONS: &[(&str, u32, &str)] = &[
+    ("8 Euro", 800, "Coins800"),
+    ("6 Euro", 600, "Coins600"),
+    ("4 Euro", 400, "Coins400"),
+    ("2 Euro", 200, "Coins200"),
+    ("1 Euro", 100, "Coins100"),
+    ("60 Cent", 60, "Coins60"),
+    ("50 Cent", 50, "Coins50"),
+    ("30 Cent", 30, "Coins30"),
+    ("20 Cent", 20, "Coins20"),
+    ("15 Cent", 15, "Coins15"),
+    ("10 Cent", 10, "Coins10"),
+    ("5 Cent", 5, "Coins5"),
+];
+
+/// Event of the "Cash Money" button.
+pub const TAKE_CASH_EVENT: &str = "Take Cash Money";
+/// Event of the "BoardComputer" button.
+pub const BOARD_COMPUTER_EVENT: &str = "Select Boardcomputer";
+
+/// Returns the coin events paying out `cents` with as few coins as possible.
+///
+/// Amounts that are not a multiple of 5 cents are rounded down.
+pub fn change_events(cents: u32) -> Vec<&'static str> {
+    let mut rest = cents;
+    let mut events = Vec::new();
+    for (_, value, event) in COIN_BUTTONS {
+        while rest >= *value {
+            events.push(*event);
+            rest -= value;
+        }
+    }
+    events
+}
+
+/// Pays out `cents` as change by pressing the coin buttons.
+pub async fn give_change(
+    config: &RequestConfig,
+    cents: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for event in change_events(cents) {
+        send_telemetry_bus_event(config, event).await?;
+    }
+    Ok(())
+}
+
+/// Takes the money handed over by the passenger.
+pub async fn take_cash_money(config: &RequestConfig) -> Result<(), Box<dyn std::error::Error>> {
+    send_telemetry_bus_event(config, TAKE_CASH_EVENT).await
+}
+
+/// Selects the board computer (ticket printer).
+pub async fn select_board_computer(
+    config: &RequestConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    send_telemetry_bus_event(config, BOARD_COMPUTER_EVENT).await
+}
+
+/// A sale recorded by the ledger.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct LedgerEntry {
+    /// Number of the stop the sale happened at, counted from 0 (see `RevenueLedger`).
+    pub stop: usize,
+    /// Id of the sale in `BusLogic.Sales`.
+    pub id: String,
+    /// The sale as sent by the game.
+    pub value: serde_json::Value,
+    /// The typed sale, or the parse error if it doesn't match `ApiSale`.
+    pub sale: Result<ApiSale, String>,
+}
+
+impl LedgerEntry {
+    /// Returns the price of the sale, or 0 if it couldn't be parsed.
+    pub fn price(&self) -> f64 {
+        self.sale.as_ref().map_or(0.0, |s| s.price as f64)
+    }
+}
+
+/// Revenue of one stop.
+#[derive(Serialize, Debug, PartialEq, Default, Clone)]
+pub struct StopRevenue {
+    pub stop: usize,
+    pub tickets: u32,
+    pub revenue: f64,
+}
+
+/// Records new sales of `BusLogic.Sales` per stop and trip.
+///
+/// Stops are counted each time `IsAtStop` becomes true; sales before the first stop
+/// are recorded with stop 0.
+#[derive(Debug, Default)]
+pub struct RevenueLedger {
+    entries: Vec<LedgerEntry>,
+    seen: BTreeSet<String>,
+    stops: usize,
+    at_stop: bool,
+}
+
+impl RevenueLedger {
+    /// Creates an empty ledger.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a snapshot and returns the number of new sales.
+    pub fn update(&mut self, v: &ApiVehicleType) -> usize {
+        let at_stop = v.is_at_stop.eq_ignore_ascii_case("true");
+        if at_stop && !self.at_stop {
+            self.stops += 1;
+        }
+        self.at_stop = at_stop;
+        let stop = self.stops.saturating_sub(1);
+
+        let mut added = 0;
+        for (id, sale) in v.bus_logic.typed_sales() {
+            if self.seen.insert(id.clone()) {
+                self.entries.push(LedgerEntry {
+                    stop,
+                    value: v.bus_logic.sales[&id].clone(),
+                    id,
+                    sale: sale.map_err(|e| e.to_string()),
+                });
+                added += 1;
+            }
+        }
+        added
+    }
+
+    /// Returns all recorded sales in order.
+    pub fn entries(&self) -> &[LedgerEntry] {
+        &self.entries
+    }
+
+    /// Returns the sales that couldn't be parsed as `ApiSale`.
+    pub fn unparsed(&self) -> impl Iterator<Item = &LedgerEntry> {
+        self.entries.iter().filter(|e| e.sale.is_err())
+    }
+
+    /// Returns the revenue of the trip, from the sales that could be parsed.
+    pub fn total(&self) -> f64 {
+        self.entries.iter().map(LedgerEntry::price).sum()
+    }
+
+    /// Returns the revenue per stop, for stops with sales (parsed or not).
+    pub fn per_stop(&self) -> Vec<StopRevenue> {
+        let mut stops: Vec<StopRevenue> = Vec::new();
+        for entry in &self.entries {
+            match stops.last_mut() {
+                Some(s) if s.stop == entry.stop => {
+                    s.tickets += 1;
+                    s.revenue += entry.price();
+                }
+                _ => stops.push(StopRevenue {
+                    stop: entry.stop,
+                    tickets: 1,
+                    revenue: entry.price(),
+                }),
+            }
+        }
+        stops
+    }
+}
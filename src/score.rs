@@ -0,0 +1,174 @@
+//! This module grades driving quality over a stream of vehicle snapshots.
+//!
+//! Each offence is counted once per episode (e.g. one harsh braking manoeuvre spanning
+//! several snapshots is one event). The score starts at 100 and every event deducts points.
+
+use crate::api::ApiVehicleType;
+use serde::Serialize;
+use std::time::Duration;
+
+/// Deceleration (m/s²) above which braking counts as harsh.
+const HARSH_BRAKING: f32 = 3.5;
+/// Brake input above which braking counts as harsh while driving faster than `MOVING_SPEED`.
+const HARSH_BRAKE_INPUT: f32 = 0.9;
+/// Acceleration (m/s²) above which accelerating counts as harsh.
+const HARSH_ACCELERATION: f32 = 2.5;
+/// Speed (km/h) above `AllowedSpeed` that is tolerated.
+const SPEEDING_TOLERANCE: f32 = 5.0;
+/// Speed (km/h) above which the bus counts as moving.
+const MOVING_SPEED: f32 = 3.0;
+/// Yaw rate (degrees per second) and steering input above which the bus is turning.
+const TURN_YAW_RATE: f32 = 10.0;
+const TURN_STEERING: f32 = 0.2;
+/// An indicator set this long before a turn still counts for it, if it points the same way.
+const INDICATOR_LEAD: Duration = Duration::from_secs(5);
+/// Gaps between snapshots longer than this are not evaluated.
+const MAX_GAP: Duration = Duration::from_secs(5);
+
+/// Result of grading a trip.
+#[derive(Serialize, Debug, PartialEq, Default, Clone)]
+pub struct DrivingReport {
+    pub harsh_braking: u32,
+    pub harsh_acceleration: u32,
+    /// Number of times the allowed speed was exceeded.
+    pub speeding: u32,
+    /// Time spent above the allowed speed in seconds.
+    pub speeding_time_s: f64,
+    /// Number of times the bus moved with open passenger doors.
+    pub doors_open_driving: u32,
+    /// Number of departures with the fixing brake engaged.
+    pub fixing_brake_departures: u32,
+    pub turns: u32,
+    /// Number of turns without indicating in the direction of the turn.
+    pub turns_without_indicator: u32,
+    /// Score from 0 to 100.
+    pub score: f64,
+}
+
+#[derive(Debug, Default)]
+struct Episodes {
+    braking: bool,
+    accelerating: bool,
+    speeding: bool,
+    doors_open: bool,
+    fixing_brake: bool,
+    // Some(indicated) while turning
+    turn: Option<bool>,
+}
+
+/// Stateful driving quality scorer.
+#[derive(Debug, Default)]
+pub struct DrivingScore {
+    report: DrivingReport,
+    episodes: Episodes,
+    last: Option<(Duration, f32, f32)>,
+    // time and direction (-1 left, 1 right) of the last indicator
+    last_indicator: Option<(Duration, i8)>,
+}
+
+impl DrivingScore {
+    /// Creates a new scorer for an empty trip.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a snapshot taken at `time`.
+    pub fn update(&mut self, time: Duration, v: &ApiVehicleType) {
+        let speed = v.speed.abs();
+        let moving = speed >= MOVING_SPEED;
+        let yaw = v.rotation.yaw;
+
+        if v.indicator_state != 0 {
+            self.last_indicator = Some((time, v.indicator_state.signum()));
+        }
+
+        let mut acceleration = 0.0;
+        let mut yaw_rate = 0.0;
+        let mut dt = None;
+        if let Some((last_time, last_speed, last_yaw)) = self.last
+            && let Some(d) = time.checked_sub(last_time)
+            && !d.is_zero()
+            && d <= MAX_GAP
+        {
+            let secs = d.as_secs_f32();
+            acceleration = (speed - last_speed) / 3.6 / secs;
+            yaw_rate = ((yaw - last_yaw + 540.0).rem_euclid(360.0) - 180.0) / secs;
+            dt = Some(d);
+        }
+        self.last = Some((time, speed, yaw));
+
+        let braking = -acceleration >= HARSH_BRAKING || (moving && v.brake >= HARSH_BRAKE_INPUT);
+        if edge(&mut self.episodes.braking, braking) {
+            self.report.harsh_braking += 1;
+        }
+
+        if edge(
+            &mut self.episodes.accelerating,
+            acceleration >= HARSH_ACCELERATION,
+        ) {
+            self.report.harsh_acceleration += 1;
+        }
+
+        let speeding = v.allowed_speed > 0.0 && speed > v.allowed_speed + SPEEDING_TOLERANCE;
+        if edge(&mut self.episodes.speeding, speeding) {
+            self.report.speeding += 1;
+        }
+        if speeding && let Some(d) = dt {
+            self.report.speeding_time_s += d.as_secs_f64();
+        }
+
+        let doors_open = moving && v.passenger_doors_open.eq_ignore_ascii_case("true");
+        if edge(&mut self.episodes.doors_open, doors_open) {
+            self.report.doors_open_driving += 1;
+        }
+
+        let fixing_brake = moving && v.fixing_brake.eq_ignore_ascii_case("true");
+        if edge(&mut self.episodes.fixing_brake, fixing_brake) {
+            self.report.fixing_brake_departures += 1;
+        }
+
+        let turning =
+            moving && yaw_rate.abs() >= TURN_YAW_RATE && v.steering.abs() >= TURN_STEERING;
+        // negative steering turns left, like indicator state -1
+        let direction = if v.steering < 0.0 { -1 } else { 1 };
+        let indicated = self.last_indicator.is_some_and(|(t, indicator)| {
+            indicator == direction && time.saturating_sub(t) <= INDICATOR_LEAD
+        });
+        match (self.episodes.turn, turning) {
+            (None, true) => self.episodes.turn = Some(indicated),
+            (Some(was_indicated), true) => self.episodes.turn = Some(was_indicated || indicated),
+            (Some(was_indicated), false) => {
+                self.report.turns += 1;
+                if !was_indicated {
+                    self.report.turns_without_indicator += 1;
+                }
+                self.episodes.turn = None;
+            }
+            (None, false) => {}
+        }
+    }
+
+    /// Returns the report of the trip so far.
+    pub fn report(&self) -> DrivingReport {
+        let r = &self.report;
+        let penalty = 5.0 * r.harsh_braking as f64
+            + 3.0 * r.harsh_acceleration as f64
+            + 2.0 * r.speeding as f64
+            + 0.1 * r.speeding_time_s
+            + 10.0 * r.doors_open_driving as f64
+            + 10.0 * r.fixing_brake_departures as f64
+            + 2.0 * r.turns_without_indicator as f64;
+
+        DrivingReport {
+            score: (100.0 - penalty).max(0.0),
+            ..r.clone()
+        }
+    }
+}
+
+/// Updates an episode flag and returns true when a new episode starts.
+fn edge(active: &mut bool, now: bool) -> bool {
+    let started = now && !*active;
+    *active = now;
+    started
+}
@@ -0,0 +1,110 @@
+//! This module provides a trip computer fed by vehicle snapshots.
+//!
+//! Snapshots are passed together with their timestamp, so the same code works for live
+//! polling and for replays of recorded telemetry. Distance is integrated from `Speed`.
+
+use crate::api::ApiVehicleType;
+use crate::occupancy::{OccupancyReport, OccupancyTracker};
+use serde::Serialize;
+use std::time::Duration;
+
+/// Below this speed (km/h) the bus counts as standing.
+const STANDING_SPEED: f32 = 1.0;
+/// Gaps between snapshots longer than this are not integrated (paused game, lost connection).
+const MAX_GAP: Duration = Duration::from_secs(5);
+
+/// Summary of a trip.
+#[derive(Serialize, Debug, PartialEq, Default, Clone)]
+pub struct TripReport {
+    /// Distance driven in km.
+    pub distance_km: f64,
+    /// Time spent moving in seconds.
+    pub driving_time_s: f64,
+    /// Time spent standing in seconds.
+    pub standing_time_s: f64,
+    /// Average speed while moving in km/h.
+    pub average_speed_kmh: f64,
+    /// Maximum speed in km/h.
+    pub max_speed_kmh: f64,
+    /// Time spent above `AllowedSpeed` in seconds.
+    pub speeding_time_s: f64,
+    /// Number of standstills with opened passenger doors.
+    pub stops: u32,
+    /// Passenger boardings, alightings and load.
+    pub occupancy: OccupancyReport,
+}
+
+/// Stateful trip computer.
+#[derive(Debug, Default)]
+pub struct TripComputer {
+    report: TripReport,
+    last: Option<(Duration, f32)>,
+    standing: bool,
+    doors_opened: bool,
+    occupancy: OccupancyTracker,
+}
+
+impl TripComputer {
+    /// Creates a new trip computer with an empty trip.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a snapshot taken at `time` (any monotonic clock, e.g. time since start of recording).
+    pub fn update(&mut self, time: Duration, v: &ApiVehicleType) {
+        let speed = v.speed.abs();
+        let moving = speed >= STANDING_SPEED;
+
+        if let Some((last_time, last_speed)) = self.last
+            && let Some(dt) = time.checked_sub(last_time)
+            && dt <= MAX_GAP
+        {
+            let secs = dt.as_secs_f64();
+            // trapezoidal integration of km/h over seconds
+            self.report.distance_km += (last_speed + speed) as f64 / 2.0 * secs / 3600.0;
+            if moving {
+                self.report.driving_time_s += secs;
+            } else {
+                self.report.standing_time_s += secs;
+            }
+            if v.allowed_speed > 0.0 && speed > v.allowed_speed {
+                self.report.speeding_time_s += secs;
+            }
+        }
+
+        if moving {
+            self.standing = false;
+            self.doors_opened = false;
+        } else {
+            self.standing = true;
+            if !self.doors_opened && v.passenger_doors_open.eq_ignore_ascii_case("true") {
+                self.doors_opened = true;
+                self.report.stops += 1;
+            }
+        }
+
+        self.occupancy.update(v);
+        self.report.max_speed_kmh = self.report.max_speed_kmh.max(speed as f64);
+        self.last = Some((time, speed));
+    }
+
+    /// Returns true if the bus is currently standing.
+    pub fn is_standing(&self) -> bool {
+        self.standing
+    }
+
+    /// Returns the report of the trip so far.
+    pub fn report(&self) -> TripReport {
+        let mut report = self.report.clone();
+        report.occupancy = self.occupancy.report();
+        if report.driving_time_s > 0.0 {
+            report.average_speed_kmh = report.distance_km / (report.driving_time_s / 3600.0);
+        }
+        report
+    }
+
+    /// Starts a new trip.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
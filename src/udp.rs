@@ -0,0 +1,295 @@
+//! This module defines a compact binary telemetry packet and sends it over UDP.
+//!
+//! Layout of version 1 (44 bytes, little endian):
+//!
+//! | Offset | Type  | Field                                      |
+//! |--------|-------|--------------------------------------------|
+//! | 0      | [u8;2]| magic "TB"                                 |
+//! | 2      | u8    | version (1)                                |
+//! | 3      | u8    | gear selector (1: drive, 2: neutral, 3: reverse) |
+//! | 4      | u16   | flags, see `FLAG_*`                        |
+//! | 6      | u8    | indicator                                  |
+//! | 7      | u8    | fuel (percent)                             |
+//! | 8      | u16   | speed (km/h)                               |
+//! | 10     | u16   | allowed speed (km/h)                       |
+//! | 12     | u32   | sequence number                            |
+//! | 16     | f32   | rpm                                        |
+//! | 20     | f32   | steering (-1 to 1)                         |
+//! | 24     | f32   | throttle (0 to 1)                          |
+//! | 28     | f32   | brake (0 to 1)                             |
+//! | 32     | f32   | roll (degrees)                             |
+//! | 36     | f32   | pitch (degrees)                            |
+//! | 40     | f32   | yaw (degrees)                              |
+//!
+//! Later versions only append fields, so decoders may accept longer packets.
+
+use crate::api::ApiVehicleType;
+use komsi::vehicle::VehicleState;
+use std::fmt;
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+
+/// Magic bytes at the start of every packet.
+pub const PACKET_MAGIC: [u8; 2] = *b"TB";
+/// Version of the packet layout written by `TelemetryPacket::encode`.
+pub const PACKET_VERSION: u8 = 1;
+/// Length of a version 1 packet in bytes.
+pub const PACKET_LEN: usize = 44;
+
+/// Ignition is on.
+pub const FLAG_IGNITION: u16 = 1 << 0;
+/// Engine is running.
+pub const FLAG_ENGINE: u16 = 1 << 1;
+/// Hazard warning lights are on.
+pub const FLAG_WARNING_LIGHTS: u16 = 1 << 2;
+/// At least one passenger door is open.
+pub const FLAG_DOORS: u16 = 1 << 3;
+/// Parking brake is applied.
+pub const FLAG_FIXING_BRAKE: u16 = 1 << 4;
+/// Parking light or headlight is on.
+pub const FLAG_LIGHTS_MAIN: u16 = 1 << 5;
+/// High beam is on.
+pub const FLAG_LIGHTS_HIGH_BEAM: u16 = 1 << 6;
+/// Door light of the front door is on.
+pub const FLAG_FRONT_DOOR: u16 = 1 << 7;
+/// Door light of the second door is on.
+pub const FLAG_SECOND_DOOR: u16 = 1 << 8;
+/// Door light of the third door is on.
+pub const FLAG_THIRD_DOOR: u16 = 1 << 9;
+/// Door light of the fourth door is on.
+pub const FLAG_FOURTH_DOOR: u16 = 1 << 10;
+/// Stop request lamp is on.
+pub const FLAG_STOP_REQUEST: u16 = 1 << 11;
+/// Bus stop brake lamp is on.
+pub const FLAG_STOP_BRAKE: u16 = 1 << 12;
+/// Door clearance lamp is on.
+pub const FLAG_DOOR_CLEARANCE: u16 = 1 << 13;
+
+/// Error returned when decoding a packet fails.
+#[derive(Debug, PartialEq)]
+pub enum PacketError {
+    /// The packet is shorter than the layout of its version.
+    TooShort(usize),
+    /// The packet does not start with `PACKET_MAGIC`.
+    BadMagic,
+    /// The packet version is not supported by this decoder.
+    UnsupportedVersion(u8),
+}
+
+impl fmt::Display for PacketError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PacketError::TooShort(len) => write!(f, "packet too short: {} bytes", len),
+            PacketError::BadMagic => write!(f, "bad packet magic"),
+            PacketError::UnsupportedVersion(v) => write!(f, "unsupported packet version {}", v),
+        }
+    }
+}
+
+impl std::error::Error for PacketError {}
+
+/// Telemetry packet for low-latency consumers.
+#[derive(Debug, PartialEq, Default, Clone)]
+pub struct TelemetryPacket {
+    /// Sequence number, incremented for every packet sent, wraps around.
+    pub sequence: u32,
+    /// Bitwise combination of the `FLAG_*` constants.
+    pub flags: u16,
+    /// Gear selector: 1 drive, 2 neutral (also if unknown), 3 reverse.
+    pub gear_selector: u8,
+    /// Indicator: 0 off, 1 left, 2 right.
+    pub indicator: u8,
+    /// Fuel or battery level in percent (0 to 100).
+    pub fuel: u8,
+    /// Speed in km/h, rounded, also positive when reversing.
+    pub speed: u16,
+    /// Allowed speed in km/h, rounded.
+    pub maxspeed: u16,
+    /// Engine speed in revolutions per minute.
+    pub rpm: f32,
+    /// Steering input from -1 (full left) to 1 (full right).
+    pub steering: f32,
+    /// Throttle pedal from 0 to 1.
+    pub throttle: f32,
+    /// Brake pedal from 0 to 1.
+    pub brake: f32,
+    /// Roll in degrees.
+    pub roll: f32,
+    /// Pitch in degrees.
+    pub pitch: f32,
+    /// Yaw (heading) in degrees.
+    pub yaw: f32,
+}
+
+impl TelemetryPacket {
+    /// Creates a packet from a mapped vehicle state and the raw telemetry it was mapped from.
+    pub fn new(sequence: u32, state: &VehicleState, av: &ApiVehicleType) -> Self {
+        let flags = [
+            (state.ignition, FLAG_IGNITION),
+            (state.engine, FLAG_ENGINE),
+            (state.lights_warning, FLAG_WARNING_LIGHTS),
+            (state.doors, FLAG_DOORS),
+            (state.fixing_brake, FLAG_FIXING_BRAKE),
+            (state.lights_main, FLAG_LIGHTS_MAIN),
+            (state.lights_high_beam, FLAG_LIGHTS_HIGH_BEAM),
+            (state.lights_front_door, FLAG_FRONT_DOOR),
+            (state.lights_second_door, FLAG_SECOND_DOOR),
+            (state.lights_third_door, FLAG_THIRD_DOOR),
+            (state.lights_fourth_door, FLAG_FOURTH_DOOR),
+            (state.lights_stop_request, FLAG_STOP_REQUEST),
+            (state.lights_stop_brake, FLAG_STOP_BRAKE),
+            (state.door_clearance, FLAG_DOOR_CLEARANCE),
+        ]
+        .into_iter()
+        .filter(|(set, _)| *set)
+        .fold(0, |flags, (_, flag)| flags | flag);
+
+        Self {
+            sequence,
+            flags,
+            gear_selector: state.gear_selector,
+            indicator: state.indicator,
+            fuel: state.fuel,
+            speed: state.speed.min(u16::MAX as u32) as u16,
+            maxspeed: state.maxspeed.min(u16::MAX as u32) as u16,
+            rpm: av.rpm,
+            steering: av.steering,
+            throttle: av.throttle,
+            brake: av.brake,
+            roll: av.rotation.roll,
+            pitch: av.rotation.pitch,
+            yaw: av.rotation.yaw,
+        }
+    }
+
+    /// Returns true if the given `FLAG_*` is set.
+    pub fn flag(&self, flag: u16) -> bool {
+        self.flags & flag != 0
+    }
+
+    /// Encodes the packet in the current layout version.
+    pub fn encode(&self) -> [u8; PACKET_LEN] {
+        let mut buf = [0u8; PACKET_LEN];
+        buf[0..2].copy_from_slice(&PACKET_MAGIC);
+        buf[2] = PACKET_VERSION;
+        buf[3] = self.gear_selector;
+        buf[4..6].copy_from_slice(&self.flags.to_le_bytes());
+        buf[6] = self.indicator;
+        buf[7] = self.fuel;
+        buf[8..10].copy_from_slice(&self.speed.to_le_bytes());
+        buf[10..12].copy_from_slice(&self.maxspeed.to_le_bytes());
+        buf[12..16].copy_from_slice(&self.sequence.to_le_bytes());
+        let floats = [
+            self.rpm,
+            self.steering,
+            self.throttle,
+            self.brake,
+            self.roll,
+            self.pitch,
+            self.yaw,
+        ];
+        for (i, value) in floats.iter().enumerate() {
+            let offset = 16 + i * 4;
+            buf[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        }
+        buf
+    }
+
+    /// Decodes a packet. Trailing bytes of newer layout versions are ignored.
+    pub fn decode(buf: &[u8]) -> Result<Self, PacketError> {
+        if buf.len() < 3 {
+            return Err(PacketError::TooShort(buf.len()));
+        }
+        if buf[0..2] != PACKET_MAGIC {
+            return Err(PacketError::BadMagic);
+        }
+        if buf[2] < 1 {
+            return Err(PacketError::UnsupportedVersion(buf[2]));
+        }
+        if buf.len() < PACKET_LEN {
+            return Err(PacketError::TooShort(buf.len()));
+        }
+
+        let u16_at = |o: usize| u16::from_le_bytes([buf[o], buf[o + 1]]);
+        let u32_at = |o: usize| u32::from_le_bytes([buf[o], buf[o + 1], buf[o + 2], buf[o + 3]]);
+        let f32_at = |o: usize| f32::from_le_bytes([buf[o], buf[o + 1], buf[o + 2], buf[o + 3]]);
+
+        Ok(Self {
+            gear_selector: buf[3],
+            flags: u16_at(4),
+            indicator: buf[6],
+            fuel: buf[7],
+            speed: u16_at(8),
+            maxspeed: u16_at(10),
+            sequence: u32_at(12),
+            rpm: f32_at(16),
+            steering: f32_at(20),
+            throttle: f32_at(24),
+            brake: f32_at(28),
+            roll: f32_at(32),
+            pitch: f32_at(36),
+            yaw: f32_at(40),
+        })
+    }
+}
+
+/// Sends telemetry packets to a broadcast (or unicast) address.
+pub struct UdpBroadcaster {
+    socket: UdpSocket,
+    target: SocketAddr,
+    sequence: u32,
+}
+
+impl UdpBroadcaster {
+    /// Creates a sender for `target`, e.g. "192.168.1.255:37339".
+    pub fn new<A: ToSocketAddrs>(target: A) -> io::Result<Self> {
+        let target = target
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no address"))?;
+        let bind = if target.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        };
+        let socket = UdpSocket::bind(bind)?;
+        socket.set_broadcast(true)?;
+
+        Ok(Self {
+            socket,
+            target,
+            sequence: 0,
+        })
+    }
+
+    /// Sends a packet for the given state, numbering packets consecutively.
+    pub fn send(&mut self, state: &VehicleState, av: &ApiVehicleType) -> io::Result<()> {
+        let packet = TelemetryPacket::new(self.sequence, state, av);
+        self.sequence = self.sequence.wrapping_add(1);
+        self.socket.send_to(&packet.encode(), self.target)?;
+        Ok(())
+    }
+}
+
+/// Polls the game every `interval` and broadcasts a packet for each poll (feature `udp`).
+///
+/// While no vehicle can be read, no packets are sent.
+#[cfg(feature = "udp")]
+pub async fn run_udp_broadcast<A: ToSocketAddrs>(
+    config: &crate::api::RequestConfig,
+    target: A,
+    interval: std::time::Duration,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut broadcaster = UdpBroadcaster::new(target)?;
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    loop {
+        ticker.tick().await;
+        let Ok(vehicle) = crate::api::get_vehicle(config).await else {
+            continue;
+        };
+        let state = crate::api2vehicle::get_vehicle_state_from_api(vehicle.clone());
+        broadcaster.send(&state, &vehicle)?;
+    }
+}
@@ -0,0 +1,230 @@
+//! This module detects schema drift between raw telemetry payloads and the types of this crate.
+//!
+//! After a game update, run the payloads of each vehicle model through
+//! `validate_vehicle_payload` to find renamed or removed fields before they silently
+//! fall back to default values. The elements of `Buttons`, `Doors` and `Wheels` are
+//! checked against their item types and reported once per array, e.g. "Buttons[].Tooltip".
+
+use crate::api::{ApiButton, ApiDoor, ApiLamps, ApiVehicleType, ApiWheel, ApiWorldType};
+use crate::profile::BUILTIN_LAMPS;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// Result of validating one payload.
+#[derive(Debug, PartialEq, Default)]
+pub struct ValidationReport {
+    /// Vehicle model of the payload (empty for world payloads).
+    pub vehicle_model: String,
+    /// Actor name of the payload (empty for world payloads).
+    pub actor_name: String,
+    /// Fields in the payload that are not consumed by the crate.
+    pub unknown_fields: Vec<String>,
+    /// Expected fields that are absent and required, so deserialization fails.
+    pub missing_fields: Vec<String>,
+    /// Expected fields that are absent and replaced by default values.
+    pub defaulted_fields: Vec<String>,
+    /// Lamps that are only available through `ApiLamps::other`.
+    pub unmapped_lamps: Vec<String>,
+    /// Deserialization error, if the payload can not be parsed at all.
+    pub error: Option<String>,
+}
+
+impl ValidationReport {
+    /// Returns true if every expected field was found and the payload parsed.
+    ///
+    /// Unknown fields and unmapped lamps are informational and do not count.
+    pub fn is_clean(&self) -> bool {
+        self.missing_fields.is_empty() && self.defaulted_fields.is_empty() && self.error.is_none()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "model: \"{}\" ({})", self.vehicle_model, self.actor_name)?;
+        if let Some(error) = &self.error {
+            writeln!(f, "  error: {}", error)?;
+        }
+        writeln!(f, "  missing: {}", self.missing_fields.join(", "))?;
+        writeln!(f, "  defaulted: {}", self.defaulted_fields.join(", "))?;
+        writeln!(f, "  unknown: {}", self.unknown_fields.join(", "))?;
+        writeln!(f, "  unmapped lamps: {}", self.unmapped_lamps.join(", "))
+    }
+}
+
+/// Validates a raw vehicle payload (as returned by `/vehicles/<name>`) against `ApiVehicleType`.
+pub fn validate_vehicle_payload(data: &Value) -> ValidationReport {
+    let mut report = ValidationReport {
+        vehicle_model: string_field(data, "VehicleModel"),
+        actor_name: string_field(data, "ActorName"),
+        ..Default::default()
+    };
+
+    compare_fields::<ApiVehicleType>(data, "", &["AllLamps"], &mut report);
+    compare_items::<ApiButton>(data, "Buttons", &mut report);
+    compare_items::<ApiDoor>(data, "Doors", &mut report);
+    compare_items::<ApiWheel>(data, "Wheels", &mut report);
+
+    if let Some(lamps) = data.get("AllLamps").and_then(|v| v.as_object()) {
+        compare_lamps(lamps, &mut report);
+    }
+
+    if let Err(e) = serde_json::from_value::<ApiVehicleType>(data.clone()) {
+        report.error = Some(e.to_string());
+    }
+
+    report
+}
+
+/// Validates a raw world payload (as returned by `/world`) against `ApiWorldType`.
+pub fn validate_world_payload(data: &Value) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    compare_fields::<ApiWorldType>(data, "", &[], &mut report);
+
+    if let Err(e) = serde_json::from_value::<ApiWorldType>(data.clone()) {
+        report.error = Some(e.to_string());
+    }
+
+    report
+}
+
+/// Compares the elements of the array `key` with the fields `T` serializes to.
+///
+/// Fields are reported once per array as e.g. "Buttons[].Tooltip".
+fn compare_items<T: Default + Serialize + DeserializeOwned>(
+    data: &Value,
+    key: &str,
+    report: &mut ValidationReport,
+) {
+    let Some(items) = data.get(key).and_then(|v| v.as_array()) else {
+        return;
+    };
+    let prefix = format!("{}[].", key);
+    for item in items {
+        compare_fields::<T>(item, &prefix, &[], report);
+    }
+}
+
+/// Compares the fields of a payload with the fields `T` serializes to.
+///
+/// Whether an absent field is required is found out by removing it from a
+/// serialized default of `T` and deserializing that again. Reported fields are
+/// prefixed with `prefix`.
+fn compare_fields<T: Default + Serialize + DeserializeOwned>(
+    data: &Value,
+    prefix: &str,
+    skip: &[&str],
+    report: &mut ValidationReport,
+) {
+    let Ok(Value::Object(expected)) = serde_json::to_value(T::default()) else {
+        return;
+    };
+    let Some(present) = data.as_object() else {
+        let what = prefix.trim_end_matches('.');
+        let what = if what.is_empty() { "payload" } else { what };
+        report.error = Some(format!("{} is not a JSON object", what));
+        return;
+    };
+
+    for key in expected.keys() {
+        if skip.contains(&key.as_str()) {
+            continue;
+        }
+        match present.get(key) {
+            None => {
+                let mut probe = expected.clone();
+                probe.remove(key);
+                let path = format!("{}{}", prefix, key);
+                if serde_json::from_value::<T>(Value::Object(probe)).is_ok() {
+                    push_unique(&mut report.defaulted_fields, path);
+                } else {
+                    push_unique(&mut report.missing_fields, path);
+                }
+            }
+            Some(Value::Object(nested)) => {
+                if let Some(Value::Object(nested_expected)) = expected.get(key) {
+                    let path = format!("{}{}", prefix, key);
+                    unknown_nested(&path, nested_expected, nested, report);
+                }
+            }
+            Some(_) => {}
+        }
+    }
+
+    for key in present.keys() {
+        if !expected.contains_key(key) {
+            push_unique(&mut report.unknown_fields, format!("{}{}", prefix, key));
+        }
+    }
+}
+
+fn push_unique(fields: &mut Vec<String>, field: String) {
+    if !fields.contains(&field) {
+        fields.push(field);
+    }
+}
+
+/// Collects keys of a nested payload object that the nested type does not know.
+fn unknown_nested(
+    path: &str,
+    expected: &Map<String, Value>,
+    present: &Map<String, Value>,
+    report: &mut ValidationReport,
+) {
+    // an empty expected object is a map type which accepts any key
+    if expected.is_empty() {
+        return;
+    }
+    for (key, value) in present {
+        let nested_path = format!("{}.{}", path, key);
+        match (expected.get(key), value) {
+            (None, _) => push_unique(&mut report.unknown_fields, nested_path),
+            (Some(Value::Object(e)), Value::Object(p)) => {
+                unknown_nested(&nested_path, e, p, report)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Checks the typed lamps of `ApiLamps`, accepting any of their built-in names.
+fn compare_lamps(lamps: &Map<String, Value>, report: &mut ValidationReport) {
+    let Ok(Value::Object(expected)) = serde_json::to_value(ApiLamps::default()) else {
+        return;
+    };
+
+    for (canonical, aliases) in BUILTIN_LAMPS {
+        let found =
+            lamps.contains_key(*canonical) || aliases.iter().any(|a| lamps.contains_key(*a));
+        if found {
+            continue;
+        }
+
+        let mut probe = expected.clone();
+        probe.remove(*canonical);
+        let path = format!("AllLamps.{}", canonical);
+        if serde_json::from_value::<ApiLamps>(Value::Object(probe)).is_ok() {
+            report.defaulted_fields.push(path);
+        } else {
+            report.missing_fields.push(path);
+        }
+    }
+
+    for name in lamps.keys() {
+        let typed = BUILTIN_LAMPS
+            .iter()
+            .any(|(canonical, aliases)| canonical == name || aliases.contains(&name.as_str()));
+        if !typed {
+            report.unmapped_lamps.push(name.clone());
+        }
+    }
+}
+
+fn string_field(data: &Value, key: &str) -> String {
+    data.get(key)
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string()
+}
@@ -0,0 +1,207 @@
+//! This module provides a WebSocket fan-out server for browser dashboards (feature `websocket`).
+//!
+//! The game is polled once per interval, no matter how many clients are connected.
+//! Messages sent to clients:
+//! - `{"type":"snapshot","data":{"vehicle":...,"world":...}}` right after connecting
+//! - `{"type":"diff","data":{...}}` with a JSON merge patch (RFC 7386) of the changes
+//! - `{"type":"snapshot",...}` again when the vehicle changes or is absent, or when the
+//!   state holds a null value
+//!
+//! In a merge patch `null` means "delete", so a value that is really null can not be
+//! sent as a diff; a full snapshot is sent instead. Clients replace their state on every
+//! snapshot and apply diffs on top of it.
+//!
+//! Messages accepted from clients:
+//! - `{"type":"cmd","cmd":"sendeventpress?event=Horn"}`, forwarded to `send_telemetry_bus_cmd`
+
+use crate::api::{RequestConfig, get_vehicle, get_world, send_telemetry_bus_cmd};
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, mpsc};
+use tokio_tungstenite::tungstenite::Message;
+
+/// Message sent by a client.
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ClientMessage {
+    /// A command path forwarded to `send_telemetry_bus_cmd`.
+    Cmd { cmd: String },
+}
+
+/// Returns the message updating clients from `old` to `new`, or `None` if both are equal.
+///
+/// This is a diff, unless the vehicle changed (another `ActorName`) or `new` holds a null
+/// value which a merge patch can not express; then it is a full snapshot.
+pub fn update_message(old: &Value, new: &Value) -> Option<Value> {
+    let diff = json_diff(old, new)?;
+    let actor = |v: &Value| v.pointer("/vehicle/ActorName").cloned();
+    if actor(old) != actor(new) || contains_null(new) {
+        Some(json!({ "type": "snapshot", "data": new }))
+    } else {
+        Some(json!({ "type": "diff", "data": diff }))
+    }
+}
+
+fn contains_null(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Object(map) => map.values().any(contains_null),
+        Value::Array(items) => items.iter().any(contains_null),
+        _ => false,
+    }
+}
+
+/// Returns a JSON merge patch turning `old` into `new`, or `None` if both are equal.
+///
+/// Objects are diffed recursively, removed keys are set to null, everything else is replaced.
+pub fn json_diff(old: &Value, new: &Value) -> Option<Value> {
+    if old == new {
+        return None;
+    }
+
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            let mut patch = Map::new();
+            for (key, new_value) in new {
+                match old.get(key) {
+                    Some(old_value) => {
+                        if let Some(diff) = json_diff(old_value, new_value) {
+                            patch.insert(key.clone(), diff);
+                        }
+                    }
+                    None => {
+                        patch.insert(key.clone(), new_value.clone());
+                    }
+                }
+            }
+            for key in old.keys() {
+                if !new.contains_key(key) {
+                    patch.insert(key.clone(), Value::Null);
+                }
+            }
+            Some(Value::Object(patch))
+        }
+        _ => Some(new.clone()),
+    }
+}
+
+/// Runs the WebSocket server on `listen` (e.g. "0.0.0.0:37338"), polling the game every `interval`.
+pub async fn run_websocket_server(
+    config: &RequestConfig,
+    listen: &str,
+    interval: Duration,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let listener = TcpListener::bind(listen).await?;
+
+    // latest state; updated and broadcast under the same lock so new clients never miss a diff
+    let state = Arc::new(Mutex::new(json!({ "vehicle": null, "world": null })));
+    let (updates, _) = broadcast::channel::<String>(64);
+    let (commands, mut pending) = mpsc::unbounded_channel::<String>();
+
+    let accept_state = Arc::clone(&state);
+    let accept_updates = updates.clone();
+    tokio::spawn(async move {
+        loop {
+            let Ok((stream, _)) = listener.accept().await else {
+                continue;
+            };
+            let (snapshot, receiver) = match accept_state.lock() {
+                Ok(s) => (
+                    json!({ "type": "snapshot", "data": *s }).to_string(),
+                    accept_updates.subscribe(),
+                ),
+                Err(_) => continue,
+            };
+            tokio::spawn(handle_client(
+                stream,
+                snapshot,
+                receiver,
+                commands.clone(),
+                Arc::clone(&accept_state),
+            ));
+        }
+    });
+
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        tokio::select! {
+            Some(cmd) = pending.recv() => {
+                if let Err(e) = send_telemetry_bus_cmd(config, &cmd).await {
+                    eprintln!("Failed to send command \"{}\": {}", cmd, e);
+                }
+            }
+            _ = ticker.tick() => {
+                let vehicle = match get_vehicle(config).await {
+                    Ok(v) => serde_json::to_value(&v)?,
+                    Err(_) => Value::Null,
+                };
+                let world = match get_world(config).await {
+                    Ok(w) => serde_json::to_value(&w)?,
+                    Err(_) => Value::Null,
+                };
+                let new_state = json!({ "vehicle": vehicle, "world": world });
+
+                if let Ok(mut s) = state.lock()
+                    && let Some(message) = update_message(&s, &new_state)
+                {
+                    // sending fails only if no client is connected
+                    let _ = updates.send(message.to_string());
+                    *s = new_state;
+                }
+            }
+        }
+    }
+}
+
+async fn handle_client(
+    stream: TcpStream,
+    snapshot: String,
+    mut updates: broadcast::Receiver<String>,
+    commands: mpsc::UnboundedSender<String>,
+    state: Arc<Mutex<Value>>,
+) {
+    let Ok(ws) = tokio_tungstenite::accept_async(stream).await else {
+        return;
+    };
+    let (mut sink, mut incoming) = ws.split();
+
+    if sink.send(Message::Text(snapshot)).await.is_err() {
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            update = updates.recv() => {
+                let text = match update {
+                    Ok(text) => text,
+                    // a slow client missed diffs, start over with a fresh snapshot
+                    Err(broadcast::error::RecvError::Lagged(_)) => {
+                        let Ok(s) = state.lock() else { break };
+                        updates = updates.resubscribe();
+                        json!({ "type": "snapshot", "data": *s }).to_string()
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                if sink.send(Message::Text(text)).await.is_err() {
+                    break;
+                }
+            }
+            message = incoming.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    match serde_json::from_str::<ClientMessage>(&text) {
+                        Ok(ClientMessage::Cmd { cmd }) => {
+                            let _ = commands.send(cmd);
+                        }
+                        Err(e) => eprintln!("Ignoring websocket message \"{}\": {}", text, e),
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! This module reads the wiper state, steps the wiper switch and provides an automatic wiper.
+//!
+//! The wiper switch ("Wiper" button) has the positions Off, Interval, On and Fast, changed
+//! one step at a time with the actions "WiperUp" and "WiperDown".
+//!
+//! The komsi `VehicleState` has no wiper field, so the wiper state is not mapped to komsi.
+
+use crate::api::{
+    ApiButton, ApiVehicleType, ApiWorldType, RequestConfig, send_telemetry_bus_event,
+};
+
+const WIPER_BUTTON: &str = "Wiper";
+const WIPER_UP_EVENT: &str = "WiperUp";
+const WIPER_DOWN_EVENT: &str = "WiperDown";
+/// Names and actions of washer buttons, compared case insensitively. None of the
+/// known payloads has a washer, so these follow the naming of the other buttons.
+const WASHER_BUTTONS: &[&str] = &["Washer", "Wiper Washer", "WindowWasher", "Window Washer"];
+const WASHER_ACTIONS: &[&str] = &["Washer", "WiperWasher", "WindowWasher", "ToggleWasher"];
+
+/// Position of the wiper switch.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default)]
+pub enum WiperPosition {
+    #[default]
+    Off,
+    Interval,
+    On,
+    Fast,
+}
+
+impl WiperPosition {
+    /// Parses a state of the "Wiper" button (case insensitive).
+    pub fn from_state(state: &str) -> Option<Self> {
+        match state.to_ascii_lowercase().as_str() {
+            "off" => Some(WiperPosition::Off),
+            "interval" => Some(WiperPosition::Interval),
+            "on" => Some(WiperPosition::On),
+            "fast" => Some(WiperPosition::Fast),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of steps above Off.
+    pub fn step(&self) -> i32 {
+        *self as i32
+    }
+}
+
+/// Wiper state of the vehicle.
+#[derive(Debug, PartialEq, Default, Clone)]
+pub struct WiperState {
+    /// Position of the wiper switch, if the vehicle has one.
+    pub position: Option<WiperPosition>,
+    /// `WiperLevel` as reported by the vehicle.
+    pub level: i32,
+    /// True if the wiper runs intermittently.
+    pub intermittent: bool,
+    /// State of a washer button, if the vehicle has one.
+    pub washer: Option<bool>,
+}
+
+impl WiperState {
+    /// Reads the wiper state from the telemetry.
+    pub fn from_vehicle(v: &ApiVehicleType) -> Self {
+        let position = v
+            .get_button(WIPER_BUTTON)
+            .and_then(|b| WiperPosition::from_state(&b.state));
+        let washer = v
+            .buttons
+            .iter()
+            .find(|b| is_washer_button(b))
+            .map(|b| b.switch_state().unwrap_or(false));
+
+        Self {
+            position,
+            level: v.wiper_level,
+            intermittent: position == Some(WiperPosition::Interval),
+            washer,
+        }
+    }
+}
+
+/// Returns true if the button is a washer button by its name or one of its actions.
+fn is_washer_button(button: &ApiButton) -> bool {
+    WASHER_BUTTONS
+        .iter()
+        .any(|w| w.eq_ignore_ascii_case(&button.name))
+        || button
+            .actions
+            .iter()
+            .any(|a| WASHER_ACTIONS.iter().any(|w| w.eq_ignore_ascii_case(a)))
+}
+
+/// Returns the events moving the wiper switch from `from` to `to`.
+pub fn wiper_events(from: WiperPosition, to: WiperPosition) -> Vec<&'static str> {
+    let steps = to.step() - from.step();
+    let event = if steps > 0 {
+        WIPER_UP_EVENT
+    } else {
+        WIPER_DOWN_EVENT
+    };
+    vec![event; steps.unsigned_abs() as usize]
+}
+
+/// Moves the wiper switch one step up or down.
+pub async fn step_wiper(
+    config: &RequestConfig,
+    up: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let event = if up { WIPER_UP_EVENT } else { WIPER_DOWN_EVENT };
+    send_telemetry_bus_event(config, event).await
+}
+
+/// Moves the wiper switch to `position`.
+pub async fn set_wiper(
+    config: &RequestConfig,
+    v: &ApiVehicleType,
+    position: WiperPosition,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let current = WiperState::from_vehicle(v)
+        .position
+        .ok_or("this vehicle has no wiper switch")?;
+    for event in wiper_events(current, position) {
+        send_telemetry_bus_event(config, event).await?;
+    }
+    Ok(())
+}
+
+/// Chooses the wiper position from the world `RainIntensity`.
+///
+/// The thresholds have a hysteresis, so the wiper does not flap when the rain
+/// intensity hovers around a threshold.
+#[derive(Debug, Default)]
+pub struct AutoWiper {
+    position: WiperPosition,
+}
+
+/// Rain intensities at which the wiper steps up to Interval, On and Fast.
+const RAIN_THRESHOLDS: [f32; 3] = [0.05, 0.3, 0.7];
+const RAIN_HYSTERESIS: f32 = 0.03;
+
+impl AutoWiper {
+    /// Creates a new automatic wiper, starting with the wiper off.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the wiper position for the given rain intensity (0 to 1).
+    pub fn update(&mut self, rain_intensity: f32) -> WiperPosition {
+        let positions = [
+            WiperPosition::Off,
+            WiperPosition::Interval,
+            WiperPosition::On,
+            WiperPosition::Fast,
+        ];
+        let mut step = self.position.step() as usize;
+
+        while step < 3 && rain_intensity >= RAIN_THRESHOLDS[step] + RAIN_HYSTERESIS {
+            step += 1;
+        }
+        while step > 0 && rain_intensity < RAIN_THRESHOLDS[step - 1] - RAIN_HYSTERESIS {
+            step -= 1;
+        }
+
+        self.position = positions[step];
+        self.position
+    }
+
+    /// Returns the events moving the wiper switch of the vehicle to the position for the
+    /// current rain, or nothing if the vehicle has no wiper switch.
+    pub fn events(&mut self, v: &ApiVehicleType, world: &ApiWorldType) -> Vec<&'static str> {
+        let target = self.update(world.rain_intensity);
+        match WiperState::from_vehicle(v).position {
+            Some(current) => wiper_events(current, target),
+            None => Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use common::load_vehicle;
+use the_bus_telemetry::accessibility::{
+    AccessibilityState, WheelchairRequestDetector, automatic_kneeling_event, door_clearance_event,
+    kneel_event, ramp_event, wheelchair_request_event,
+};
+
+#[test]
+fn test_accessibility_state_from_payload() {
+    let state =
+        AccessibilityState::from_vehicle(&load_vehicle("BP_Mercedes_eCitaro_12m_2Door_C.json"));
+    assert_eq!(state.kneeling, Some(false));
+    assert_eq!(state.automatic_kneeling, Some(false));
+    assert_eq!(state.door_clearance, Some(false));
+    assert_eq!(state.ramp_out, Some(false));
+    assert!(!state.wheelchair_request);
+
+    // The Citea has no kneeling button, only the kneeling lamp.
+    let state = AccessibilityState::from_vehicle(&load_vehicle("vdl_citea.json"));
+    assert_eq!(state.kneeling, Some(false));
+    assert_eq!(state.automatic_kneeling, Some(false));
+    assert_eq!(state.ramp_out, None);
+}
+
+#[test]
+fn test_accessibility_events() {
+    let man = load_vehicle("man_lionscity.json");
+    assert_eq!(kneel_event(&man, true).as_deref(), Some("KneelDown"));
+    assert_eq!(kneel_event(&man, false).as_deref(), Some("KneelUp"));
+    assert_eq!(
+        automatic_kneeling_event(&man).as_deref(),
+        Some("ToggleAutomaticKneeling")
+    );
+    assert_eq!(
+        door_clearance_event(&man).as_deref(),
+        Some("ToggleDoorClearance")
+    );
+    assert_eq!(ramp_event(&man, true), None);
+
+    let ecitaro = load_vehicle("BP_Mercedes_eCitaro_12m_2Door_C.json");
+    assert_eq!(
+        ramp_event(&ecitaro, true).as_deref(),
+        Some("ElectricRampOut")
+    );
+    assert_eq!(
+        ramp_event(&ecitaro, false).as_deref(),
+        Some("ElectricRampIn")
+    );
+
+    let urbino = load_vehicle("solaris_urbino.json");
+    assert_eq!(
+        automatic_kneeling_event(&urbino).as_deref(),
+        Some("toggleAutoKneeling")
+    );
+    assert_eq!(
+        wheelchair_request_event(&urbino).as_deref(),
+        Some("WheelchairRequest")
+    );
+
+    // button names match exactly, ignoring case only
+    let mut man = man;
+    let button = man
+        .buttons
+        .iter_mut()
+        .find(|b| b.name == "DoorClearance")
+        .unwrap();
+    button.name = "DOORCLEARANCE".to_string();
+    assert!(door_clearance_event(&man).is_some());
+    let button = man
+        .buttons
+        .iter_mut()
+        .find(|b| b.name == "DOORCLEARANCE")
+        .unwrap();
+    button.name = "Door_Clearance".to_string();
+    assert_eq!(door_clearance_event(&man), None);
+}
+
+#[test]
+fn test_wheelchair_request_detector() {
+    let mut v = load_vehicle("man_lionscity.json");
+    let mut detector = WheelchairRequestDetector::new();
+    assert_eq!(detector.update(&v), None);
+
+    v.all_lamps
+        .other
+        .insert("LightDoorWheelchairRequestButtonMiddle".to_string(), 1.0);
+    let request = detector.update(&v).expect("wheelchair request");
+    assert_eq!(request.doors, vec!["Middle".to_string()]);
+    assert!(AccessibilityState::from_vehicle(&v).wheelchair_request);
+
+    // The request is only reported once.
+    assert_eq!(detector.update(&v), None);
+}
@@ -1,3 +1,5 @@
+mod common;
+
 #[cfg(test)]
 mod tests {
     use serde_json::json;
@@ -399,3 +401,97 @@ fn test_api_vehicle_get_button_returns_struct() {
     assert!(gear2.is_some());
     assert_eq!(gear2.unwrap().state, "Neutral");
 }
+
+#[test]
+fn test_api_lamps_keeps_all_lamps() {
+    // MAN Lion's City
+    let vehicle = common::load_vehicle("man_lionscity.json");
+    let lamps = &vehicle.all_lamps;
+
+    // untyped lamps are kept
+    assert_eq!(lamps.other.get("LED ABS"), Some(&0.0));
+    assert_eq!(lamps.other.get("LightBrake"), Some(&1.0));
+    assert!(lamps.other.contains_key("LED_DashboardInfoRed"));
+
+    // typed lamps are not duplicated in the other map
+    assert!(!lamps.other.contains_key("LightParking1"));
+    assert!(!lamps.other.contains_key("LED StopRequest"));
+
+    let all = lamps.all();
+    assert!(all.contains_key("Light Parking"));
+    assert!(all.contains_key("LightInteriorLowerDeck"));
+    assert!(all.len() > 30);
+}
+
+#[test]
+fn test_api_lamps_skips_non_numeric_lamps() {
+    use the_bus_telemetry::api::ApiVehicleType;
+
+    let mut data = common::load_json("man_lionscity.json");
+    let lamps = data["AllLamps"].as_object_mut().unwrap();
+    lamps.insert("LED Future".to_string(), serde_json::json!("on"));
+    lamps.insert("LED Group".to_string(), serde_json::json!({ "Left": 1.0 }));
+    lamps.insert("LED Missing".to_string(), serde_json::Value::Null);
+
+    let vehicle: ApiVehicleType = serde_json::from_value(data).expect("lamps not skipped");
+    let lamps = &vehicle.all_lamps;
+    assert!(!lamps.other.contains_key("LED Future"));
+    assert!(!lamps.other.contains_key("LED Group"));
+    assert!(!lamps.other.contains_key("LED Missing"));
+    assert_eq!(lamps.other.get("LightBrake"), Some(&1.0));
+    assert_eq!(lamps.light_parking, 1.0);
+}
+
+#[test]
+fn test_api_lamps_canonical_lookup() {
+    use the_bus_telemetry::api::canonical_lamp_name;
+
+    assert_eq!(canonical_lamp_name("LightParking1"), canonical_lamp_name("Light Parking"));
+    assert_eq!(canonical_lamp_name("Light Travelling"), canonical_lamp_name("LightTraveling1"));
+    assert_eq!(canonical_lamp_name("Light Reverse"), canonical_lamp_name("LightReversal"));
+    assert_eq!(canonical_lamp_name("LED_ABS"), canonical_lamp_name("LED ABS"));
+    assert_ne!(canonical_lamp_name("Door Button 1"), canonical_lamp_name("Door Button 2"));
+
+    // eCitaro names its lamps differently from the MAN
+    let ecitaro = common::load_vehicle("BP_Mercedes_eCitaro_12m_2Door_C.json");
+    let man = common::load_vehicle("man_lionscity.json");
+
+    assert_eq!(ecitaro.all_lamps.get("LightBrake"), ecitaro.all_lamps.other.get("Light BRAKE").copied());
+    assert_eq!(man.all_lamps.get("Light BRAKE"), Some(1.0));
+    assert_eq!(man.all_lamps.get("Light Reverse"), Some(0.0));
+    assert_eq!(man.all_lamps.get("LightParking"), Some(1.0));
+    assert_eq!(man.all_lamps.get("__does_not_exist__"), None);
+}
+
+#[test]
+fn test_lamp_tables_agree() {
+    use the_bus_telemetry::api::{ApiLamps, canonical_lamp_name, known_lamp_names};
+    use the_bus_telemetry::profile::BUILTIN_LAMPS;
+
+    let known: Vec<&str> = known_lamp_names().collect();
+    for name in ApiLamps::default().all().keys() {
+        let canonical = canonical_lamp_name(name);
+        assert!(known.contains(&canonical.as_str()), "{} has no variant group", name);
+    }
+
+    for (name, aliases) in BUILTIN_LAMPS {
+        assert!(known.contains(&canonical_lamp_name(name).as_str()), "{} has no variant group", name);
+        for alias in aliases.iter() {
+            assert_eq!(canonical_lamp_name(alias), canonical_lamp_name(name), "{}", alias);
+
+            // the serde aliases of ApiLamps accept the same names
+            let mut lamps = serde_json::json!({
+                "LightHeadlight": 0.0,
+                "Light Parking": 0.0,
+                "LightTraveling": 0.0,
+                "Door Button 1": 0.0,
+                "Door Button 2": 0.0
+            });
+            lamps.as_object_mut().unwrap().remove(*name);
+            lamps[*alias] = serde_json::json!(1.0);
+            let lamps: ApiLamps = serde_json::from_value(lamps).expect(alias);
+            assert_eq!(lamps.get(name), Some(1.0), "{}", alias);
+            assert!(lamps.other.is_empty(), "{} not typed", alias);
+        }
+    }
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use common::{load_vehicle, load_world};
+use the_bus_telemetry::climate::{
+    ClimateState, ClimateSwitch, switch_event, temperature_event, temperature_step_event,
+    toggle_event,
+};
+
+#[test]
+fn test_climate_state_man() {
+    let world = load_world();
+    let climate = ClimateState::from_vehicle(&load_vehicle("man_lionscity.json"), Some(&world));
+
+    assert_eq!(climate.air_condition, Some(false));
+    assert_eq!(climate.target_temperature, Some(20.0));
+    assert_eq!(climate.mode.as_deref(), Some("4"));
+    assert_eq!(climate.fan_speed, Some(1.0));
+    assert_eq!(climate.ac_driver, Some(false));
+    assert_eq!(climate.podest_heating, Some(false));
+    assert_eq!(climate.window_heating_2, Some(false));
+    assert_eq!(climate.outside_temperature, Some(world.temperature));
+    assert!((climate.temperature_difference().unwrap() - (20.0 - world.temperature)).abs() < 1e-5);
+}
+
+#[test]
+fn test_climate_state_other_models() {
+    let vdl = ClimateState::from_vehicle(&load_vehicle("vdl_citea.json"), None);
+    assert_eq!(vdl.air_condition, Some(false));
+    assert_eq!(vdl.target_temperature, None);
+    assert_eq!(vdl.podest_heating, None);
+    assert_eq!(vdl.temperature_difference(), None);
+
+    let ecitaro =
+        ClimateState::from_vehicle(&load_vehicle("BP_Mercedes_eCitaro_12m_2Door_C.json"), None);
+    assert!((ecitaro.fan_speed.unwrap() - 76.233184).abs() < 1e-4);
+    assert_eq!(ecitaro.window_heating_1, Some(false));
+}
+
+#[test]
+fn test_climate_events() {
+    let man = load_vehicle("man_lionscity.json");
+    assert_eq!(
+        toggle_event(&man, ClimateSwitch::AirCondition).as_deref(),
+        Some("ToggleAirCondition")
+    );
+    assert_eq!(
+        toggle_event(&man, ClimateSwitch::PodestHeating).as_deref(),
+        Some("TogglePodestHeating")
+    );
+    // "AC Driver" has no action
+    assert_eq!(toggle_event(&man, ClimateSwitch::AcDriver), None);
+
+    assert_eq!(temperature_event(&man, 21.3).as_deref(), Some("SetTemp215"));
+    assert_eq!(temperature_event(&man, 35.0).as_deref(), Some("SetTemp280"));
+    assert_eq!(
+        temperature_step_event(&man, true).as_deref(),
+        Some("AirconditionKeyUp")
+    );
+
+    let vdl = load_vehicle("vdl_citea.json");
+    assert_eq!(temperature_event(&vdl, 21.0), None);
+    assert_eq!(
+        temperature_step_event(&vdl, false).as_deref(),
+        Some("AirconditionMinus")
+    );
+}
+
+#[test]
+fn test_climate_switch_event_only_when_state_differs() {
+    let man = load_vehicle("man_lionscity.json");
+    assert_eq!(
+        switch_event(&man, ClimateSwitch::AirCondition, true)
+            .unwrap()
+            .as_deref(),
+        Some("ToggleAirCondition")
+    );
+    assert_eq!(
+        switch_event(&man, ClimateSwitch::AirCondition, false).unwrap(),
+        None
+    );
+    // already off, so the missing action does not matter
+    assert_eq!(
+        switch_event(&man, ClimateSwitch::AcDriver, false).unwrap(),
+        None
+    );
+    assert!(switch_event(&man, ClimateSwitch::AcDriver, true).is_err());
+
+    let vdl = load_vehicle("vdl_citea.json");
+    assert!(switch_event(&vdl, ClimateSwitch::PodestHeating, false).is_err());
+}
@@ -0,0 +1,21 @@
+#![allow(dead_code)]
+
+use std::fs;
+use the_bus_telemetry::{ApiVehicleType, ApiWorldType};
+
+/// Reads a fixture from tests/json as raw JSON.
+pub fn load_json(name: &str) -> serde_json::Value {
+    let file = fs::read_to_string(format!("tests/json/{}", name))
+        .unwrap_or_else(|_| panic!("{} not found", name));
+    serde_json::from_str(&file).expect("invalid json")
+}
+
+/// Reads a vehicle fixture from tests/json.
+pub fn load_vehicle(name: &str) -> ApiVehicleType {
+    serde_json::from_value(load_json(name)).expect("invalid vehicle")
+}
+
+/// Reads the world fixture.
+pub fn load_world() -> ApiWorldType {
+    serde_json::from_value(load_json("world.json")).expect("invalid world")
+}
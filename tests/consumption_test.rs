@@ -0,0 +1,117 @@
+mod common;
+
+use common::load_vehicle;
+use std::time::Duration;
+use the_bus_telemetry::ApiVehicleType;
+use the_bus_telemetry::consumption::{ConsumptionTracker, EnergyKind};
+
+fn snapshot(speed: f32, current_fuel: f32) -> ApiVehicleType {
+    ApiVehicleType {
+        speed,
+        current_fuel,
+        max_fuel: 400.0,
+        low_fuel_warning: "false".to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_energy_kind_by_model() {
+    let ecitaro = load_vehicle("BP_Mercedes_eCitaro_12m_2Door_C.json");
+    assert_eq!(EnergyKind::from_vehicle(&ecitaro), EnergyKind::Electric);
+    assert_eq!(EnergyKind::Electric.unit(), "kWh");
+
+    for name in [
+        "man_lionscity.json",
+        "scania_citywide.json",
+        "vdl_citea.json",
+    ] {
+        assert_eq!(
+            EnergyKind::from_vehicle(&load_vehicle(name)),
+            EnergyKind::Diesel
+        );
+    }
+}
+
+#[test]
+fn test_consumption_per_100km_and_range() {
+    let mut tracker = ConsumptionTracker::new();
+
+    // 3600 s at 36 km/h = 36 km, using 18 l
+    for s in 0..=3600 {
+        let fuel = 300.0 - 18.0 * s as f32 / 3600.0;
+        tracker.update(Duration::from_secs(s), &snapshot(36.0, fuel));
+    }
+
+    let report = tracker.report();
+    assert_eq!(report.energy, EnergyKind::Diesel);
+    assert_eq!(report.unit, "l");
+    assert!((report.distance_km - 36.0).abs() < 1e-6);
+    assert!((report.consumed - 18.0).abs() < 0.01);
+    assert!((report.per_100km.unwrap() - 50.0).abs() < 0.05);
+    assert!((report.range_km.unwrap() - 282.0 / 50.0 * 100.0).abs() < 0.5);
+    assert!(report.refuels.is_empty());
+}
+
+#[test]
+fn test_refuel_events() {
+    let mut tracker = ConsumptionTracker::new().energy(EnergyKind::Electric);
+
+    tracker.update(Duration::from_secs(0), &snapshot(0.0, 100.0));
+    tracker.update(Duration::from_secs(1), &snapshot(0.0, 99.0));
+    tracker.update(Duration::from_secs(2), &snapshot(0.0, 150.0));
+    // small increase from recuperation
+    tracker.update(Duration::from_secs(3), &snapshot(0.0, 150.2));
+
+    let report = tracker.report();
+    assert_eq!(report.energy, EnergyKind::Electric);
+    assert_eq!(report.refuels.len(), 1);
+    assert_eq!(report.refuels[0].time_s, 2.0);
+    assert!((report.refuels[0].amount - 51.0).abs() < 1e-4);
+    assert!((report.consumed - 1.0).abs() < 1e-4);
+    assert!((report.recovered - 0.2).abs() < 1e-3);
+
+    // not enough distance for a consumption figure
+    assert_eq!(report.per_100km, None);
+    assert_eq!(report.range_km, None);
+}
+
+#[test]
+fn test_small_rises_are_recovered_not_consumed() {
+    let mut ecitaro = load_vehicle("BP_Mercedes_eCitaro_12m_2Door_C.json");
+    let mut tracker = ConsumptionTracker::new();
+
+    // recuperation while braking, then a drop while accelerating
+    for (s, fuel) in [(0, 240.0), (1, 240.3), (2, 240.6), (3, 240.9), (4, 240.5)] {
+        ecitaro.current_fuel = fuel;
+        tracker.update(Duration::from_secs(s), &ecitaro);
+    }
+
+    let report = tracker.report();
+    assert_eq!(report.energy, EnergyKind::Electric);
+    assert!(report.refuels.is_empty());
+    assert!((report.recovered - 0.9).abs() < 1e-3);
+    assert!((report.consumed - 0.4).abs() < 1e-3);
+}
+
+#[test]
+fn test_recuperation_lowers_consumption_and_extends_range() {
+    let mut tracker = ConsumptionTracker::new().energy(EnergyKind::Electric);
+
+    // 3600 s at 36 km/h = 36 km, recovering 0.004 kWh every fifth second and
+    // using 0.01 kWh in all other seconds: 28.8 kWh consumed, 2.88 kWh recovered
+    let mut charge = 200.0f32;
+    for s in 0..=3600u64 {
+        if s > 0 {
+            charge += if s % 5 == 0 { 0.004 } else { -0.01 };
+        }
+        tracker.update(Duration::from_secs(s), &snapshot(36.0, charge));
+    }
+
+    let report = tracker.report();
+    assert!((report.consumed - 28.8).abs() < 0.05);
+    assert!((report.recovered - 2.88).abs() < 0.05);
+    let net = report.consumed - report.recovered;
+    assert!((report.per_100km.unwrap() - net / 36.0 * 100.0).abs() < 1e-6);
+    assert!((report.range_km.unwrap() - report.current / net * 36.0).abs() < 1e-3);
+}
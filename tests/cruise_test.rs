@@ -0,0 +1,122 @@
+mod common;
+
+use common::load_vehicle;
+use the_bus_telemetry::cruise::{CruiseAction, CruiseController, CruiseState, DisengageReason};
+use the_bus_telemetry::{ApiButton, ApiVehicleType};
+
+fn driving(speed: f32) -> ApiVehicleType {
+    let mut v = load_vehicle("man_lionscity.json");
+    v.speed = speed;
+    v.brake = 0.0;
+    v.passenger_doors_open = "false".to_string();
+    v.fixing_brake = "false".to_string();
+    v.allowed_speed = 50.0;
+    // none of the payloads offers the throttle event
+    v.buttons.push(ApiButton {
+        name: "Pedals".to_string(),
+        actions: vec!["Throttle".to_string()],
+        ..Default::default()
+    });
+    v
+}
+
+#[test]
+fn test_cruise_state_from_payload() {
+    let v = load_vehicle("man_lionscity.json");
+    let cruise = CruiseState::from_vehicle(&v);
+
+    assert!(!cruise.active);
+    assert_eq!(cruise.allowed_speed, v.allowed_speed);
+    assert!(cruise.buttons.is_empty());
+
+    // only exact button names count, not lamps or other buttons mentioning the cruise control
+    let mut v = load_vehicle("man_lionscity.json");
+    for name in ["ButtonLight Cruise Control", "tempomat"] {
+        v.buttons.push(ApiButton {
+            name: name.to_string(),
+            ..Default::default()
+        });
+    }
+    assert_eq!(CruiseState::from_vehicle(&v).buttons, vec!["tempomat"]);
+
+    let mut v = driving(60.0);
+    v.cruise_control_active = "true".to_string();
+    let cruise = CruiseState::from_vehicle(&v);
+    assert!(cruise.active);
+    assert!(cruise.is_speeding());
+}
+
+#[test]
+fn test_cruise_controller_holds_speed() {
+    let mut cruise = CruiseController::new();
+    assert_eq!(cruise.update(&driving(20.0)), None);
+
+    cruise.engage(&driving(20.0), 40.0).unwrap();
+    assert_eq!(
+        cruise.update(&driving(20.0)),
+        Some(CruiseAction::Accelerate)
+    );
+    assert_eq!(cruise.update(&driving(39.5)), None);
+    assert_eq!(cruise.update(&driving(40.0)), Some(CruiseAction::Coast));
+    // Within the tolerance the throttle stays released.
+    assert_eq!(cruise.update(&driving(39.5)), None);
+    assert_eq!(
+        cruise.update(&driving(38.0)),
+        Some(CruiseAction::Accelerate)
+    );
+}
+
+#[test]
+fn test_cruise_controller_safeguards() {
+    let mut cruise = CruiseController::new();
+    cruise.engage(&driving(20.0), 40.0).unwrap();
+    assert_eq!(
+        cruise.update(&driving(20.0)),
+        Some(CruiseAction::Accelerate)
+    );
+
+    let mut v = driving(25.0);
+    v.brake = 0.5;
+    assert_eq!(cruise.update(&v), Some(CruiseAction::Coast));
+    assert!(!cruise.is_engaged());
+    assert_eq!(cruise.disengaged_by(), Some(DisengageReason::Brake));
+    assert_eq!(cruise.update(&driving(20.0)), None);
+
+    cruise.engage(&driving(20.0), 40.0).unwrap();
+    assert_eq!(
+        cruise.update(&driving(20.0)),
+        Some(CruiseAction::Accelerate)
+    );
+    let mut v = driving(20.0);
+    v.passenger_doors_open = "true".to_string();
+    assert_eq!(cruise.update(&v), Some(CruiseAction::Coast));
+    assert_eq!(cruise.disengaged_by(), Some(DisengageReason::DoorsOpen));
+}
+
+#[test]
+fn test_cruise_controller_limiter() {
+    let mut cruise = CruiseController::new().limiter(true);
+    cruise.engage(&driving(45.0), 70.0).unwrap();
+
+    assert_eq!(cruise.effective_target(&driving(45.0)), 50.0);
+    assert_eq!(
+        cruise.update(&driving(45.0)),
+        Some(CruiseAction::Accelerate)
+    );
+    assert_eq!(cruise.update(&driving(50.0)), Some(CruiseAction::Coast));
+}
+
+#[test]
+fn test_cruise_controller_needs_throttle_event() {
+    let mut cruise = CruiseController::new();
+    assert!(
+        cruise
+            .engage(&load_vehicle("man_lionscity.json"), 40.0)
+            .is_err()
+    );
+    assert!(!cruise.is_engaged());
+    assert_eq!(cruise.update(&driving(20.0)), None);
+
+    let mut cruise = CruiseController::new().throttle_event("Accelerate");
+    assert!(cruise.engage(&driving(20.0), 40.0).is_err());
+}
@@ -0,0 +1,168 @@
+mod common;
+
+use common::{load_vehicle, load_world};
+use std::time::Duration;
+use the_bus_telemetry::csv_logger::{
+    CsvLogger, EXTRA_LAMPS_COLUMN, default_columns, flatten_vehicle,
+};
+
+#[test]
+fn test_flatten_vehicle_columns() {
+    let vehicle = load_vehicle("BP_Solaris_Urbino_18m_4D_C.json");
+    let row = flatten_vehicle(&vehicle);
+    let columns: Vec<&str> = row.iter().map(|(c, _)| c.as_str()).collect();
+
+    assert!(columns.contains(&"vehicle.speed"));
+    assert!(columns.contains(&"vehicle.steering"));
+    assert!(columns.contains(&"vehicle.throttle"));
+    assert!(columns.contains(&"vehicle.brake"));
+    assert!(columns.contains(&"vehicle.gearbox.current_gear"));
+    assert!(columns.contains(&"vehicle.location.x"));
+    assert!(columns.contains(&"lamp.lightparking"));
+    assert!(columns.contains(&"door.0.open"));
+    assert!(columns.contains(&"wheel.5.on_ground"));
+    assert!(!columns.contains(&"wheel.6.on_ground"));
+
+    // columns are unique
+    let mut unique = columns.clone();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(unique.len(), columns.len());
+}
+
+#[test]
+fn test_lamp_columns_shared_across_models() {
+    let ecitaro = flatten_vehicle(&load_vehicle("BP_Mercedes_eCitaro_12m_2Door_C.json"));
+    let man = flatten_vehicle(&load_vehicle("man_lionscity.json"));
+
+    let lamps = |row: &[(String, String)]| -> Vec<String> {
+        row.iter()
+            .filter(|(c, _)| c.starts_with("lamp."))
+            .map(|(c, _)| c.clone())
+            .collect()
+    };
+    let man_lamps = lamps(&man);
+    for column in [
+        "lamp.lightparking",
+        "lamp.ledstoprequest",
+        "lamp.doorbutton1",
+    ] {
+        assert!(lamps(&ecitaro).contains(&column.to_string()));
+        assert!(man_lamps.contains(&column.to_string()));
+    }
+}
+
+#[test]
+fn test_csv_logger_writes_header_once() {
+    let vehicle = load_vehicle("BP_Mercedes_eCitaro_12m_2Door_C.json");
+    let world = load_world();
+
+    let mut logger = CsvLogger::new(Vec::new());
+    logger
+        .log(Duration::from_millis(1000), Some(&vehicle), Some(&world))
+        .unwrap();
+    logger
+        .log(Duration::from_millis(1250), Some(&vehicle), None)
+        .unwrap();
+
+    let output = String::from_utf8(logger.into_inner()).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("time_ms,vehicle.actor_name,"));
+    assert!(lines[0].contains("world.temperature"));
+    assert!(lines[1].starts_with("1000,"));
+    assert!(lines[2].starts_with("1250,"));
+
+    // missing world values are left empty
+    assert_eq!(lines[0].split(',').count(), lines[2].split(',').count());
+    assert!(lines[2].ends_with(','));
+}
+
+#[test]
+fn test_csv_logger_fixed_columns() {
+    let vehicle = load_vehicle("vdl_citea.json");
+    let columns = vec![
+        "time_ms".to_string(),
+        "vehicle.vehicle_model".to_string(),
+        "lamp.unknownlamp".to_string(),
+    ];
+
+    let mut logger = CsvLogger::with_columns(Vec::new(), columns);
+    logger
+        .log(Duration::from_millis(5), Some(&vehicle), None)
+        .unwrap();
+
+    let output = String::from_utf8(logger.into_inner()).unwrap();
+    assert_eq!(
+        output,
+        format!(
+            "time_ms,vehicle.vehicle_model,lamp.unknownlamp\n5,{},\n",
+            vehicle.vehicle_model
+        )
+    );
+}
+
+#[test]
+fn test_csv_logger_header_without_vehicle_in_first_row() {
+    let vehicle = load_vehicle("man_lionscity.json");
+    let world = load_world();
+
+    // no bus loaded yet when the logger starts
+    let mut logger = CsvLogger::new(Vec::new());
+    logger.log(Duration::ZERO, None, Some(&world)).unwrap();
+    logger
+        .log(Duration::from_millis(100), Some(&vehicle), Some(&world))
+        .unwrap();
+
+    let output = String::from_utf8(logger.into_inner()).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    let header: Vec<&str> = lines[0].split(',').collect();
+    assert_eq!(header.len(), default_columns().len());
+
+    let model = header
+        .iter()
+        .position(|c| *c == "vehicle.vehicle_model")
+        .unwrap();
+    let parking = header
+        .iter()
+        .position(|c| *c == "lamp.lightparking")
+        .unwrap();
+    let first: Vec<&str> = lines[1].split(',').collect();
+    let second: Vec<&str> = lines[2].split(',').collect();
+    assert_eq!(first[model], "");
+    assert_eq!(second[model], vehicle.vehicle_model);
+    assert_eq!(second[parking], "1");
+}
+
+#[test]
+fn test_csv_logger_main_light_and_extra_lamps() {
+    let ecitaro = load_vehicle("BP_Mercedes_eCitaro_12m_2Door_C.json");
+    let columns = default_columns();
+    assert!(columns.contains(&"lamp.lightmain".to_string()));
+    assert!(
+        flatten_vehicle(&ecitaro)
+            .iter()
+            .any(|(c, _)| c == "lamp.lightmain")
+    );
+
+    let man = load_vehicle("man_lionscity.json");
+    let mut logger = CsvLogger::new(Vec::new());
+    logger.log(Duration::ZERO, Some(&ecitaro), None).unwrap();
+    logger.log(Duration::ZERO, Some(&man), None).unwrap();
+
+    let output = String::from_utf8(logger.into_inner()).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    let header: Vec<&str> = lines[0].split(',').collect();
+    let main = header.iter().position(|c| *c == "lamp.lightmain").unwrap();
+    let extra = header
+        .iter()
+        .position(|c| *c == EXTRA_LAMPS_COLUMN)
+        .unwrap();
+    let row: Vec<&str> = lines[1].split(',').collect();
+    assert_eq!(row[main], ecitaro.all_lamps.light_main.to_string());
+
+    // model specific lamps are kept in the extra column
+    let row: Vec<&str> = lines[2].split(',').collect();
+    assert!(row[extra].split(';').any(|lamp| lamp == "ledabs=0"));
+    assert!(!row[extra].contains("lightparking="));
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use common::load_vehicle;
+use serde_json::json;
+use the_bus_telemetry::destination::{
+    BoardComputerData, DestinationDisplay, board_computer_path, destination_command, line_command,
+};
+
+#[test]
+fn test_board_computer_path() {
+    let v = load_vehicle("man_lionscity.json");
+    assert!(v.destination_display_active.eq_ignore_ascii_case("true"));
+    assert_eq!(
+        board_computer_path(&v).as_deref(),
+        Some("vehicles/BP_MAN_LionsCityDD_Base_C_2147417439/boardcomputer")
+    );
+
+    let mut v = v;
+    v.umg.clear();
+    assert_eq!(board_computer_path(&v), None);
+}
+
+#[test]
+fn test_board_computer_data_from_value() {
+    let value = json!({
+        "Ibis": {
+            "Line Number": 42,
+            "Route": "03",
+            "Destination": { "DestinationCode": "117", "DestinationName": "Hauptbahnhof" }
+        },
+        "Stops": [{ "NextStop": "Rathaus" }]
+    });
+    let data = BoardComputerData::from_value(&value);
+
+    assert_eq!(data.line.as_deref(), Some("42"));
+    assert_eq!(data.route.as_deref(), Some("03"));
+    assert_eq!(data.destination.as_deref(), Some("Hauptbahnhof"));
+    assert_eq!(data.destination_code.as_deref(), Some("117"));
+    assert_eq!(data.next_stop.as_deref(), Some("Rathaus"));
+    assert_eq!(data.raw, value);
+}
+
+#[test]
+fn test_destination_display() {
+    let v = load_vehicle("scania_citywide.json");
+    let display = DestinationDisplay::from_vehicle(&v, None);
+    assert!(display.active);
+    assert_eq!(display.line, None);
+
+    let data = BoardComputerData::from_value(&json!({ "Line": "7", "Destination": "Zoo" }));
+    let display = DestinationDisplay::from_vehicle(&v, Some(&data));
+    assert_eq!(display.line.as_deref(), Some("7"));
+    assert_eq!(display.destination.as_deref(), Some("Zoo"));
+}
+
+#[test]
+fn test_code_commands() {
+    let mut v = load_vehicle("man_lionscity.json");
+    assert_eq!(
+        line_command(&v, "042").as_deref(),
+        Some("vehicles/BP_MAN_LionsCityDD_Base_C_2147417439/boardcomputer/setline?line=042")
+    );
+    assert_eq!(
+        destination_command(&v, "117").as_deref(),
+        Some(
+            "vehicles/BP_MAN_LionsCityDD_Base_C_2147417439/boardcomputer/setdestination?destination=117"
+        )
+    );
+    assert_eq!(line_command(&v, ""), None);
+    assert_eq!(destination_command(&v, "1&x=2"), None);
+
+    // the commands follow the "Atron" path of the vehicle
+    v.umg
+        .insert("Atron".to_string(), "/vehicles/Bus/ibis".to_string());
+    assert_eq!(
+        line_command(&v, "7").as_deref(),
+        Some("vehicles/Bus/ibis/setline?line=7")
+    );
+    v.umg.clear();
+    assert_eq!(line_command(&v, "7"), None);
+}
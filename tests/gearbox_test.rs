@@ -0,0 +1,104 @@
+mod common;
+
+use common::load_vehicle;
+use the_bus_telemetry::ApiVehicleType;
+use the_bus_telemetry::gearbox::{
+    GearEvent, GearSelector, GearboxState, GearboxTracker, selector_event,
+};
+use the_bus_telemetry::get_vehicle_state_from_api;
+
+fn set_button_state(v: &mut ApiVehicleType, name: &str, state: &str) {
+    v.buttons
+        .iter_mut()
+        .find(|b| b.name == name)
+        .unwrap_or_else(|| panic!("{} not found", name))
+        .state = state.to_string();
+}
+
+#[test]
+fn test_gearbox_state_from_payload() {
+    let gearbox = GearboxState::from_vehicle(&load_vehicle("man_lionscity.json"));
+    assert_eq!(gearbox.kind, "TorqueConverter");
+    assert_eq!(gearbox.selector, Some(GearSelector::Neutral));
+    assert_eq!(gearbox.current_gear, 0);
+    assert_eq!(gearbox.gear_count, 4);
+    assert!(!gearbox.shifting);
+
+    let v = load_vehicle("BP_Mercedes_eCitaro_12m_2Door_C.json");
+    assert_eq!(v.gearbox.current_selector, "N");
+    assert_eq!(GearboxState::from_vehicle(&v).gear_count, 0);
+}
+
+#[test]
+fn test_gear_selector_sources() {
+    assert_eq!(GearSelector::from_state("D"), Some(GearSelector::Drive));
+    assert_eq!(GearSelector::from_state("3"), Some(GearSelector::Drive));
+    assert_eq!(GearSelector::from_state("-1"), Some(GearSelector::Reverse));
+    assert_eq!(GearSelector::from_state("Park"), None);
+
+    // Push buttons, when the selector state is unknown.
+    let mut v = load_vehicle("BP_Solaris_Urbino_18m_4D_C.json");
+    set_button_state(&mut v, "Gear Selector", "");
+    set_button_state(&mut v, "Gear Reverse", "On");
+    assert_eq!(GearSelector::from_vehicle(&v), Some(GearSelector::Reverse));
+    assert_eq!(get_vehicle_state_from_api(v).gear_selector, 3);
+
+    // Gearbox.CurrentSelector without any selector button.
+    let mut v = load_vehicle("BP_Mercedes_eCitaro_12m_2Door_C.json");
+    v.buttons.retain(|b| b.name != "Gear Selector");
+    v.gearbox.current_selector = "D".to_string();
+    assert_eq!(get_vehicle_state_from_api(v).gear_selector, 1);
+
+    // Manual gears from the gearbox.
+    let mut v = load_vehicle("vdl_citea.json");
+    v.buttons.retain(|b| b.name != "Gear Selector");
+    v.gearbox.current_gear = 2;
+    assert_eq!(GearSelector::from_vehicle(&v), Some(GearSelector::Drive));
+}
+
+#[test]
+fn test_gearbox_tracker_events() {
+    let mut v = load_vehicle("scania_citywide.json");
+    let mut tracker = GearboxTracker::new();
+    assert!(tracker.update(&v).is_empty());
+
+    set_button_state(&mut v, "Gear Selector", "Drive");
+    v.gearbox.target_gear = 1;
+    assert_eq!(
+        tracker.update(&v),
+        vec![
+            GearEvent::SelectorChanged {
+                from: Some(GearSelector::Neutral),
+                to: Some(GearSelector::Drive)
+            },
+            GearEvent::ShiftStarted { from: 0, to: 1 }
+        ]
+    );
+
+    v.gearbox.current_gear = 1;
+    assert_eq!(
+        tracker.update(&v),
+        vec![GearEvent::Shifted { from: 0, to: 1 }]
+    );
+    assert!(tracker.update(&v).is_empty());
+}
+
+#[test]
+fn test_selector_event() {
+    let man = load_vehicle("man_lionscity.json");
+    assert_eq!(
+        selector_event(&man, GearSelector::Drive).as_deref(),
+        Some("SetGearD")
+    );
+
+    // The Urbino 18m selector only steps, the push buttons select directly.
+    let urbino = load_vehicle("BP_Solaris_Urbino_18m_4D_C.json");
+    assert_eq!(
+        selector_event(&urbino, GearSelector::Neutral).as_deref(),
+        Some("SetGearN")
+    );
+
+    let mut v = load_vehicle("BP_Mercedes_eCitaro_12m_2Door_C.json");
+    v.buttons.retain(|b| b.name != "Gear Selector");
+    assert_eq!(selector_event(&v, GearSelector::Reverse), None);
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use common::load_vehicle;
+use std::time::Duration;
+use the_bus_telemetry::ApiVehicleType;
+use the_bus_telemetry::indicator::{BlinkEdge, IndicatorMode, IndicatorPhase, IndicatorTracker};
+
+fn set_lamp(v: &mut ApiVehicleType, name: &str, value: f32) {
+    v.all_lamps.other.insert(name.to_string(), value);
+}
+
+#[test]
+fn test_indicator_phase_from_lamps() {
+    let mut v = load_vehicle("man_lionscity.json");
+    assert_eq!(IndicatorPhase::from_vehicle(&v), IndicatorPhase::default());
+
+    v.indicator_state = -1;
+    set_lamp(&mut v, "IndicatorLeft", 1.0);
+    let phase = IndicatorPhase::from_vehicle(&v);
+    assert_eq!(phase.mode, IndicatorMode::Left);
+    assert!(phase.left);
+    assert!(!phase.right);
+
+    // Dark half of the blink cycle.
+    set_lamp(&mut v, "IndicatorLeft", 0.0);
+    assert!(!IndicatorPhase::from_vehicle(&v).lit());
+
+    // The eCitaro names its lamps "Light Indicator Left/Right".
+    let mut v = load_vehicle("BP_Mercedes_eCitaro_12m_2Door_C.json");
+    v.warning_lights = "true".to_string();
+    set_lamp(&mut v, "Light Indicator Left", 1.0);
+    set_lamp(&mut v, "Light Indicator Right", 1.0);
+    let phase = IndicatorPhase::from_vehicle(&v);
+    assert_eq!(phase.mode, IndicatorMode::Hazard);
+    assert!(phase.left && phase.right);
+}
+
+#[test]
+fn test_indicator_phase_from_value() {
+    let mut v = load_vehicle("man_lionscity.json");
+    v.all_lamps
+        .other
+        .retain(|name, _| !name.contains("Indicator"));
+    v.warning_lights = "true".to_string();
+    v.indicator_value = 1.0;
+
+    let phase = IndicatorPhase::from_vehicle(&v);
+    assert!(phase.left && phase.right);
+
+    v.warning_lights = "false".to_string();
+    v.indicator_state = 1;
+    let phase = IndicatorPhase::from_vehicle(&v);
+    assert!(!phase.left && phase.right);
+}
+
+#[test]
+fn test_indicator_tracker() {
+    let mut v = load_vehicle("man_lionscity.json");
+    let mut tracker = IndicatorTracker::new();
+    v.indicator_state = 1;
+
+    let mut edges = Vec::new();
+    for (ms, lit) in [
+        (0, true),
+        (100, true),
+        (350, false),
+        (700, true),
+        (1050, false),
+    ] {
+        set_lamp(&mut v, "IndicatorRight", if lit { 1.0 } else { 0.0 });
+        edges.push(tracker.update(Duration::from_millis(ms), &v));
+    }
+
+    assert_eq!(
+        edges,
+        vec![
+            Some(BlinkEdge::On),
+            None,
+            Some(BlinkEdge::Off),
+            Some(BlinkEdge::On),
+            Some(BlinkEdge::Off)
+        ]
+    );
+    assert_eq!(tracker.period(), Some(Duration::from_millis(700)));
+
+    v.indicator_state = 0;
+    assert_eq!(tracker.update(Duration::from_millis(1400), &v), None);
+    assert_eq!(tracker.period(), None);
+}
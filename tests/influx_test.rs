@@ -0,0 +1,117 @@
+mod common;
+
+use common::{load_vehicle, load_world};
+use std::net::UdpSocket;
+use std::time::Duration;
+use the_bus_telemetry::influx::{send_udp, vehicle_lines, world_lines, write_lines};
+
+#[test]
+fn test_vehicle_lines() {
+    let vehicle = load_vehicle("BP_Mercedes_eCitaro_12m_2Door_C.json");
+    let lines = vehicle_lines(&vehicle, Duration::from_secs(2));
+
+    let main = &lines[0];
+    assert!(main.starts_with("thebus_vehicle,actor=BP_Mercedes_eCitaro_12m_2Door_C_"));
+    assert!(main.contains(",model=eCitybus "));
+    assert!(main.contains("num_seats="));
+    assert!(main.ends_with(" 2000000000"));
+
+    assert!(lines.iter().any(|l| l.starts_with("thebus_fuel,")));
+    assert!(lines.iter().any(|l| l.starts_with("thebus_position,")));
+    assert_eq!(
+        lines
+            .iter()
+            .filter(|l| l.starts_with("thebus_door,"))
+            .count(),
+        vehicle.doors.len()
+    );
+    assert_eq!(
+        lines
+            .iter()
+            .filter(|l| l.starts_with("thebus_wheel,"))
+            .count(),
+        vehicle.wheels.len()
+    );
+
+    // lamp names with spaces are escaped
+    assert!(
+        lines
+            .iter()
+            .any(|l| l.starts_with("thebus_lamp,") && l.contains("Light\\ Parking="))
+    );
+}
+
+#[test]
+fn test_world_lines_written() {
+    let world = load_world();
+
+    let lines = world_lines(&world, Duration::from_nanos(42));
+    let mut out = Vec::new();
+    write_lines(&mut out, &lines).unwrap();
+
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.starts_with("thebus_world,level="));
+    assert!(out.ends_with(" 42\n"));
+    assert!(out.contains("rain_intensity="));
+}
+
+#[test]
+fn test_send_udp_batches_lines() {
+    let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+    receiver
+        .set_read_timeout(Some(Duration::from_secs(2)))
+        .unwrap();
+    let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+
+    let vehicle = load_vehicle("BP_Solaris_Urbino_18m_4D_C.json");
+    let lines = vehicle_lines(&vehicle, Duration::from_secs(1));
+    send_udp(&sender, receiver.local_addr().unwrap(), &lines).unwrap();
+
+    let mut received = String::new();
+    let mut buf = [0u8; 2048];
+    while received.lines().count() < lines.len() {
+        let (n, _) = receiver.recv_from(&mut buf).unwrap();
+        received.push_str(std::str::from_utf8(&buf[..n]).unwrap());
+    }
+    assert_eq!(received.lines().collect::<Vec<_>>(), lines);
+}
+
+#[test]
+fn test_vehicle_lines_skip_non_finite_and_split_lamps() {
+    let mut vehicle = load_vehicle("BP_Mercedes_eCitaro_12m_2Door_C.json");
+    vehicle.speed = f32::NAN;
+    vehicle.rpm = f32::INFINITY;
+    for i in 0..100 {
+        vehicle
+            .all_lamps
+            .other
+            .insert(format!("Custom Lamp {}", i), 1.0);
+    }
+
+    let lines = vehicle_lines(&vehicle, Duration::from_secs(1));
+    assert!(
+        lines
+            .iter()
+            .all(|l| !l.contains("NaN") && !l.contains("inf"))
+    );
+    assert!(lines[0].contains(" allowed_speed="));
+    assert!(!lines[0].contains(",speed=") && !lines[0].contains(",rpm="));
+
+    let lamps: Vec<&String> = lines
+        .iter()
+        .filter(|l| l.starts_with("thebus_lamp,"))
+        .collect();
+    assert!(lamps.len() > 1);
+    assert!(
+        lamps
+            .iter()
+            .all(|l| l.len() < 1400 && l.ends_with(" 1000000000"))
+    );
+    assert_eq!(
+        lamps
+            .iter()
+            .map(|l| l.matches("Custom\\ Lamp\\ ").count())
+            .sum::<usize>(),
+        100
+    );
+}
@@ -1,7 +1,6 @@
 use std::fs;
 use std::path::Path;
 use the_bus_telemetry::api::{ApiVehicleType, ApiWorldType};
-use serde_json;
 use komsi::komsi::KomsiDateTime;
 
 
@@ -22,10 +21,10 @@ fn test_json_deserialization_from_files() {
 fn test_world_deserialization(file_path: &str) {
     let file = Path::new(file_path);
     let json = fs::read_to_string(file)
-        .expect(&format!("Failed to read {}", file_path));
+        .unwrap_or_else(|_| panic!("Failed to read {}", file_path));
 
     let world: ApiWorldType = serde_json::from_str(&json)
-        .expect(&format!("Failed to deserialize {}", file_path));
+        .unwrap_or_else(|_| panic!("Failed to deserialize {}", file_path));
 
     assert_eq!(world.level_name, "Castrop");
     assert_eq!(world.date_time, "2026-01-01T09:43:48");
@@ -48,10 +47,10 @@ fn test_vehicle_deserialization(file_path: &str, actor_name: &str) {
 
     // Read and deserialize the JSON file
     let json = fs::read_to_string(file)
-        .expect(&format!("Failed to read {}", file_path));
+        .unwrap_or_else(|_| panic!("Failed to read {}", file_path));
 
     let vehicle: ApiVehicleType = serde_json::from_str(&json)
-        .expect(&format!("Failed to deserialize {}", file_path));
+        .unwrap_or_else(|_| panic!("Failed to deserialize {}", file_path));
 
     // Basic validation
     assert!(!vehicle.actor_name.is_empty(), "Actor name should not be empty");
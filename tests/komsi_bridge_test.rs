@@ -0,0 +1,90 @@
+use komsi::vehicle::VehicleState;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use the_bus_telemetry::komsi_bridge::KomsiBridge;
+
+/// Sink collecting the written bytes, failing while `broken` is set.
+#[derive(Clone, Default)]
+struct TestSink {
+    written: Arc<Mutex<Vec<u8>>>,
+    broken: Arc<Mutex<bool>>,
+}
+
+impl Write for TestSink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if *self.broken.lock().unwrap() {
+            return Err(io::Error::new(io::ErrorKind::BrokenPipe, "unplugged"));
+        }
+        self.written.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn state(ignition: bool, speed: u32) -> VehicleState {
+    VehicleState {
+        ignition,
+        speed,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_bridge_writes_only_changes() {
+    let sink = TestSink::default();
+    let connects = Arc::new(Mutex::new(0));
+
+    let (s, c) = (sink.clone(), Arc::clone(&connects));
+    let mut bridge = KomsiBridge::new(move || {
+        *c.lock().unwrap() += 1;
+        Ok(s.clone())
+    });
+
+    // the first update writes the complete state
+    assert!(bridge.update(state(true, 0)).unwrap() > 0);
+    assert!(bridge.is_connected());
+
+    // nothing changed, nothing written
+    assert_eq!(bridge.update(state(true, 0)).unwrap(), 0);
+
+    let before = sink.written.lock().unwrap().len();
+    assert!(bridge.update(state(true, 30)).unwrap() > 0);
+    assert!(sink.written.lock().unwrap().len() > before);
+    assert_eq!(*connects.lock().unwrap(), 1);
+}
+
+#[test]
+fn test_bridge_reconnects_after_write_error() {
+    let sink = TestSink::default();
+    let connects = Arc::new(Mutex::new(0));
+
+    let (s, c) = (sink.clone(), Arc::clone(&connects));
+    let mut bridge = KomsiBridge::new(move || {
+        *c.lock().unwrap() += 1;
+        Ok(s.clone())
+    });
+
+    bridge.update(state(true, 0)).unwrap();
+
+    *sink.broken.lock().unwrap() = true;
+    assert!(bridge.update(state(true, 30)).is_err());
+    assert!(!bridge.is_connected());
+
+    // after reconnecting the complete state is written again, even if unchanged
+    *sink.broken.lock().unwrap() = false;
+    assert!(bridge.update(state(true, 30)).unwrap() > 0);
+    assert_eq!(*connects.lock().unwrap(), 2);
+}
+
+#[test]
+fn test_bridge_connect_error() {
+    let mut bridge = KomsiBridge::new(|| -> io::Result<TestSink> {
+        Err(io::Error::new(io::ErrorKind::NotFound, "no such device"))
+    });
+
+    assert!(bridge.update(state(true, 0)).is_err());
+    assert!(!bridge.is_connected());
+}
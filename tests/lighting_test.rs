@@ -0,0 +1,102 @@
+mod common;
+
+use common::load_vehicle;
+use the_bus_telemetry::get_vehicle_state_from_api;
+use the_bus_telemetry::lighting::{LightSwitchPosition, LightingState, light_switch_events};
+
+#[test]
+fn test_lighting_state_man() {
+    let v = load_vehicle("man_lionscity.json");
+    let lighting = LightingState::from_vehicle(&v);
+
+    assert_eq!(lighting.switch, Some(LightSwitchPosition::Off));
+    assert!(lighting.parking.on);
+    assert_eq!(lighting.parking.intensity, 1.0);
+    assert!(!lighting.headlight.on);
+    assert!(lighting.daytime.on);
+    assert!(lighting.position.on);
+    assert!(lighting.brake.on);
+    assert!(!lighting.reverse.on);
+    assert!(!lighting.fog.on);
+    assert!(!lighting.rear_fog.on);
+    assert!(!lighting.dashboard);
+
+    let state = get_vehicle_state_from_api(v);
+    assert!(state.lights_main);
+    assert!(!state.lights_high_beam);
+}
+
+#[test]
+fn test_lighting_state_flags_and_variants() {
+    let mut v = load_vehicle("BP_Mercedes_eCitaro_12m_2Door_C.json");
+    let lighting = LightingState::from_vehicle(&v);
+    assert!(lighting.main.on);
+    assert!(lighting.daytime.on);
+    assert!(lighting.brake.on);
+    assert!(!lighting.fog.on);
+
+    // The top-level flags switch a lamp on even without a lamp entry.
+    v.fog_light = "true".to_string();
+    v.dashboard_light = "true".to_string();
+    let lighting = LightingState::from_vehicle(&v);
+    assert!(lighting.fog.on);
+    assert_eq!(lighting.fog.intensity, 0.0);
+    assert!(lighting.dashboard);
+
+    v.parking_light = "true".to_string();
+    v.headlight = "true".to_string();
+    v.traveller_light = "true".to_string();
+    let lighting = LightingState::from_vehicle(&v);
+    assert!(lighting.parking.on);
+    assert!(lighting.headlight.on);
+    assert!(lighting.high_beam.on);
+    assert_eq!(lighting.high_beam.intensity, 0.0);
+}
+
+#[test]
+fn test_light_switch_position() {
+    assert_eq!(
+        LightSwitchPosition::from_state("Parking Lights"),
+        Some(LightSwitchPosition::ParkingLights)
+    );
+    assert_eq!(
+        LightSwitchPosition::from_state("Rear Fog Light"),
+        Some(LightSwitchPosition::RearFogLight)
+    );
+    assert_eq!(LightSwitchPosition::from_state("Primary"), None);
+}
+
+#[test]
+fn test_light_switch_events() {
+    let ecitaro = load_vehicle("BP_Mercedes_eCitaro_12m_2Door_C.json");
+    assert_eq!(
+        light_switch_events(&ecitaro, LightSwitchPosition::Headlights),
+        Some(vec!["LightSwitchUp".to_string(); 2])
+    );
+    assert_eq!(
+        light_switch_events(&ecitaro, LightSwitchPosition::Off),
+        Some(vec![])
+    );
+
+    // The Urbino 18m switch has no fog light positions and only "Fake" actions.
+    let urbino = load_vehicle("BP_Solaris_Urbino_18m_4D_C.json");
+    assert_eq!(
+        light_switch_events(&urbino, LightSwitchPosition::ParkingLights),
+        None
+    );
+    assert_eq!(
+        light_switch_events(&urbino, LightSwitchPosition::FrontFogLight),
+        None
+    );
+
+    let mut man = load_vehicle("man_lionscity.json");
+    man.buttons
+        .iter_mut()
+        .find(|b| b.name == "Light Switch")
+        .unwrap()
+        .state = "Headlights".to_string();
+    assert_eq!(
+        light_switch_events(&man, LightSwitchPosition::Off),
+        Some(vec!["LightSwitchDown".to_string(); 2])
+    );
+}
@@ -0,0 +1,29 @@
+mod common;
+
+use common::{load_vehicle, load_world};
+use the_bus_telemetry::metrics::render_metrics;
+
+#[test]
+fn test_render_metrics() {
+    let vehicle = load_vehicle("man_lionscity.json");
+    let world = load_world();
+
+    let text = render_metrics(Some(&vehicle), Some(&world));
+
+    let labels = r#"actor="BP_MAN_LionsCityDD_Base_C_2147417439",model="Lions City""#;
+    assert!(text.contains("thebus_up 1\n"));
+    assert!(text.contains(&format!("thebus_vehicle_seats{{{}}} 83\n", labels)));
+    assert!(text.contains(&format!("thebus_vehicle_door_open{{{},door=\"Door Front\"}} 1\n", labels)));
+    assert!(text.contains(&format!("thebus_vehicle_lamp{{{},lamp=\"LED ABS\"}} 0\n", labels)));
+    assert!(text.contains("# TYPE thebus_vehicle_rpm gauge\n"));
+    assert!(text.contains("thebus_world_wind_intensity{level=\"Castrop\"} 0.4"));
+}
+
+#[test]
+fn test_render_metrics_without_vehicle() {
+    let text = render_metrics(None, None);
+    assert_eq!(
+        text,
+        "# HELP thebus_up 1 if the player is in a vehicle and telemetry could be read.\n# TYPE thebus_up gauge\nthebus_up 0\n"
+    );
+}
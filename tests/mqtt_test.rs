@@ -0,0 +1,85 @@
+#![cfg(feature = "mqtt")]
+
+mod common;
+
+use common::{load_json, load_vehicle};
+use rumqttc::{AsyncClient, Event, EventLoop, MqttOptions, Packet, QoS};
+use std::time::Duration;
+use the_bus_telemetry::api2vehicle::get_vehicle_state_from_api;
+use the_bus_telemetry::mqtt::{MqttPublisher, vehicle_messages, vehicle_state_topics};
+
+#[test]
+fn test_vehicle_state_topics() {
+    let vehicle = load_vehicle("man_lionscity.json");
+    let state = get_vehicle_state_from_api(vehicle);
+
+    let topics = vehicle_state_topics("thebus", &state);
+
+    assert!(topics.contains(&("thebus/state/ignition".to_string(), "true".to_string())));
+    assert!(topics.contains(&("thebus/state/doors".to_string(), "true".to_string())));
+    assert!(topics.contains(&("thebus/state/gear_selector".to_string(), "2".to_string())));
+    assert!(topics.contains(&("thebus/state/fuel".to_string(), "99".to_string())));
+    assert!(topics.iter().all(|(t, _)| t.starts_with("thebus/state/")));
+}
+
+#[test]
+fn test_vehicle_messages_keep_raw_payload() {
+    let mut raw = load_json("man_lionscity.json");
+    raw["FutureField"] = serde_json::json!({ "Nested": true });
+
+    let messages = vehicle_messages("thebus", &raw);
+    let (topic, payload) = &messages[0];
+    assert_eq!(topic, "thebus/telemetry/vehicle");
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(payload).unwrap(),
+        raw
+    );
+    assert!(messages.contains(&("thebus/state/ignition".to_string(), "true".to_string())));
+
+    // a payload that is not a vehicle is still published as received
+    let messages = vehicle_messages("thebus", &serde_json::json!({ "Error": "no vehicle" }));
+    assert_eq!(messages.len(), 1);
+}
+
+/// Returns the payload of the next publish received by `eventloop`.
+async fn next_publish(eventloop: &mut EventLoop) -> Vec<u8> {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            if let Event::Incoming(Packet::Publish(p)) = eventloop.poll().await.unwrap() {
+                return p.payload.to_vec();
+            }
+        }
+    })
+    .await
+    .expect("no message received")
+}
+
+#[tokio::test]
+#[ignore = "needs an MQTT broker on 127.0.0.1:1883, e.g. mosquitto"]
+async fn test_publisher_with_local_broker() {
+    let prefix = "thebus-test";
+    let raw = load_json("man_lionscity.json");
+
+    let options = MqttOptions::new("thebus-test-publisher", "127.0.0.1", 1883);
+    let (client, mut eventloop) = AsyncClient::new(options, 64);
+    tokio::spawn(async move { while eventloop.poll().await.is_ok() {} });
+    let mut publisher = MqttPublisher::new(client);
+    publisher.publish(vehicle_messages(prefix, &raw)).unwrap();
+
+    let options = MqttOptions::new("thebus-test-subscriber", "127.0.0.1", 1883);
+    let (subscriber, mut eventloop) = AsyncClient::new(options, 64);
+    subscriber
+        .subscribe(format!("{}/telemetry/vehicle", prefix), QoS::AtLeastOnce)
+        .await
+        .unwrap();
+
+    let payload = next_publish(&mut eventloop).await;
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(&payload).unwrap(),
+        raw
+    );
+
+    // clearing publishes an empty retained message
+    publisher.clear(prefix).unwrap();
+    assert!(next_publish(&mut eventloop).await.is_empty());
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use common::{load_json, load_vehicle};
+use std::time::Duration;
+use the_bus_telemetry::ApiVehicleType;
+use the_bus_telemetry::occupancy::OccupancyTracker;
+use the_bus_telemetry::trip::TripComputer;
+
+fn snapshot(occupied: u32, doors_open: bool) -> ApiVehicleType {
+    ApiVehicleType {
+        num_seats: 40,
+        num_occupied_seats: occupied,
+        passenger_doors_open: doors_open.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_occupancy_fields_parsed() {
+    let vehicle = load_vehicle("man_lionscity.json");
+    let data = load_json("man_lionscity.json");
+
+    assert_eq!(vehicle.num_seats as u64, data["NumSeats"].as_u64().unwrap());
+    assert_eq!(
+        vehicle.num_occupied_seats as u64,
+        data["NumOccupiedSeats"].as_u64().unwrap()
+    );
+}
+
+#[test]
+fn test_boardings_and_alightings_per_stop() {
+    let mut tracker = OccupancyTracker::new();
+
+    tracker.update(&snapshot(10, false));
+    // first stop: 3 alight, 5 board
+    tracker.update(&snapshot(10, true));
+    tracker.update(&snapshot(7, true));
+    tracker.update(&snapshot(12, true));
+    tracker.update(&snapshot(12, false));
+    // changes with closed doors are not attributed to a stop
+    tracker.update(&snapshot(13, false));
+    // second stop: everybody leaves
+    tracker.update(&snapshot(13, true));
+    tracker.update(&snapshot(0, false));
+
+    let report = tracker.report();
+    assert_eq!(report.seats, 40);
+    assert_eq!(report.load, 0);
+    assert_eq!(report.peak_load, 13);
+    assert_eq!(report.boardings, 5);
+    assert_eq!(report.alightings, 16);
+
+    assert_eq!(report.stops.len(), 2);
+    assert_eq!(report.stops[0].boardings, 5);
+    assert_eq!(report.stops[0].alightings, 3);
+    assert_eq!(report.stops[0].load, 12);
+    assert_eq!(report.stops[1].index, 1);
+    assert_eq!(report.stops[1].alightings, 13);
+    assert_eq!(report.stops[1].load, 0);
+}
+
+#[test]
+fn test_occupancy_in_trip_report() {
+    let mut trip = TripComputer::new();
+    trip.update(Duration::from_secs(0), &snapshot(0, false));
+    trip.update(Duration::from_secs(1), &snapshot(4, true));
+    trip.update(Duration::from_secs(2), &snapshot(4, false));
+
+    let report = trip.report();
+    assert_eq!(report.stops, 1);
+    assert_eq!(report.occupancy.boardings, 4);
+    assert_eq!(report.occupancy.stops.len(), 1);
+
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["occupancy"]["peak_load"], 4);
+}
@@ -0,0 +1,139 @@
+mod common;
+
+use common::load_json;
+use the_bus_telemetry::api2vehicle::get_vehicle_state_from_api_with_profile;
+use the_bus_telemetry::profile::MappingProfile;
+
+#[test]
+fn test_builtin_profile_gear_selector() {
+    let profile = MappingProfile::builtin();
+
+    assert_eq!(profile.state_value("Gear Selector", "Drive"), Some(1));
+    assert_eq!(profile.state_value("Gear Selector", "Neutral"), Some(2));
+    assert_eq!(profile.state_value("Gear Selector", "Reverse"), Some(3));
+    assert_eq!(profile.state_value("Gear Selector", "Park"), None);
+}
+
+#[test]
+fn test_toml_profile_extends_builtin() {
+    let source = r#"
+[lamps]
+"Light Parking" = ["LightParkingNew"]
+
+[buttons]
+"Gear Selector" = ["Gear Lever"]
+
+[states."Gear Selector"]
+D = 1
+"Reverse" = 3
+"#;
+    let profile = MappingProfile::from_toml_str(source).unwrap();
+
+    assert_eq!(profile.state_value("Gear Selector", "D"), Some(1));
+    assert_eq!(profile.state_value("Gear Selector", "Neutral"), Some(2));
+    assert_eq!(profile.lamps["Light Parking"], vec!["LightParkingNew"]);
+    assert_eq!(profile.buttons["Gear Selector"], vec!["Gear Lever"]);
+}
+
+#[test]
+fn test_profile_errors_point_at_line() {
+    let source = "[states.\"Gear Selector\"]\nDrive = 1\nPark = 7\n";
+    let err = MappingProfile::from_toml_str(source).unwrap_err();
+    assert_eq!(err.line, 3);
+
+    let source = "[lamps]\n\"Light Parking\" = [\"A\"]\n\"LED ABS\" = []\n";
+    let err = MappingProfile::from_toml_str(source).unwrap_err();
+    assert_eq!(err.line, 3);
+
+    let source = "{\n  \"lamps\": {},\n  \"colors\": {}\n}";
+    let err = MappingProfile::from_json_str(source).unwrap_err();
+    assert_eq!(err.line, 3);
+
+    let source = "[buttons]\n\"Gear Selector\" = \"Gear Lever\"\n";
+    let err = MappingProfile::from_toml_str(source).unwrap_err();
+    assert_eq!(err.line, 2);
+}
+
+#[test]
+fn test_profile_rejects_unknown_state_table() {
+    let source = "[states.\"Gear Selector\"]\nD = 1\n\n[states.\"Light Switch\"]\nOff = 0\n";
+    let err = MappingProfile::from_toml_str(source).unwrap_err();
+    assert_eq!(err.line, 4);
+    assert!(err.message.contains("Light Switch"), "{}", err.message);
+
+    let source = "{\n  \"states\": {\n    \"Door\": {}\n  }\n}";
+    let err = MappingProfile::from_json_str(source).unwrap_err();
+    assert_eq!(err.line, 3);
+}
+
+#[test]
+fn test_profile_error_line_with_repeated_key() {
+    // "Light Parking" appears twice, the error is in the second use
+    let source = "[lamps]\n\"Light Parking\" = [\"A\"]\n\n[buttons]\n\"Light Parking\" = []\n";
+    let err = MappingProfile::from_toml_str(source).unwrap_err();
+    assert_eq!(err.line, 5);
+    assert!(
+        err.message.contains("no alternative names"),
+        "{}",
+        err.message
+    );
+
+    let source = "[states.\"Gear Selector\"]\nD = 1\nDrive = 4\n";
+    let err = MappingProfile::from_toml_str(source).unwrap_err();
+    assert_eq!(err.line, 3);
+}
+
+#[test]
+fn test_profile_applied_to_payload() {
+    let mut data = load_json("BP_Mercedes_eCitaro_12m_2Door_C.json");
+
+    // simulate a renamed lamp and gear selector after a game update
+    let lamps = data["AllLamps"].as_object_mut().unwrap();
+    let parking = lamps.remove("Light Parking").unwrap();
+    lamps.insert("Light Parking Renamed".to_string(), serde_json::json!(1.0));
+    assert_eq!(parking, serde_json::json!(0.0));
+    for button in data["Buttons"].as_array_mut().unwrap() {
+        if button["Name"] == "Gear Selector" {
+            button["Name"] = serde_json::json!("Gear Lever");
+            button["State"] = serde_json::json!("D");
+        }
+    }
+
+    // without a profile the lamp is missing
+    assert!(serde_json::from_value::<the_bus_telemetry::ApiVehicleType>(data.clone()).is_err());
+
+    let source = r#"
+[lamps]
+"Light Parking" = ["Light Parking Renamed"]
+
+[buttons]
+"Gear Selector" = ["Gear Lever"]
+
+[states."Gear Selector"]
+D = 1
+"#;
+    let profile = MappingProfile::from_toml_str(source).unwrap();
+    let vehicle = profile.parse_vehicle(data).unwrap();
+    assert_eq!(vehicle.all_lamps.light_parking, 1.0);
+
+    let state = get_vehicle_state_from_api_with_profile(vehicle, &profile);
+    assert_eq!(state.gear_selector, 1);
+    assert!(state.lights_main);
+}
+
+#[test]
+fn test_profile_lamp_named_by_alias() {
+    let mut data = load_json("man_lionscity.json");
+    let lamps = data["AllLamps"].as_object_mut().unwrap();
+    assert!(lamps.contains_key("LightParking1"));
+    lamps.insert("Parking Renamed".to_string(), serde_json::json!(0.5));
+
+    // "LightParking" is a built-in alias of "Light Parking"
+    let source = r#"
+[lamps]
+"LightParking" = ["Parking Renamed"]
+"#;
+    let profile = MappingProfile::from_toml_str(source).unwrap();
+    let vehicle = profile.parse_vehicle(data).unwrap();
+    assert_eq!(vehicle.all_lamps.light_parking, 0.5);
+}
@@ -0,0 +1,104 @@
+use the_bus_telemetry::punctuality::{PunctualityTracker, Timetable, parse_game_time};
+use the_bus_telemetry::{ApiVehicleType, ApiWorldType};
+
+fn snapshot(at_stop: bool, doors_open: bool) -> ApiVehicleType {
+    ApiVehicleType {
+        is_at_stop: at_stop.to_string(),
+        passenger_doors_open: doors_open.to_string(),
+        ..Default::default()
+    }
+}
+
+fn world(time: &str) -> ApiWorldType {
+    ApiWorldType {
+        date_time: format!("2026-01-01T{}", time),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_parse_game_time() {
+    assert_eq!(parse_game_time("1970-01-01T00:00:00"), Some(0));
+    assert_eq!(parse_game_time("2026-01-01T09:43:48"), Some(1767260628));
+    assert_eq!(parse_game_time("2026-13-01T09:43:48"), None);
+    assert_eq!(parse_game_time("09:43:48"), None);
+}
+
+#[test]
+fn test_timetable_csv() {
+    let source = "stop,departure\nHauptbahnhof,09:40\n\"Markt, Nord\",09:45:30\n\n# end\n";
+    let timetable = Timetable::from_csv_str(source).unwrap();
+
+    assert_eq!(timetable.entries.len(), 2);
+    assert_eq!(timetable.entries[0].stop, "Hauptbahnhof");
+    assert_eq!(timetable.entries[0].departure, 9 * 3600 + 40 * 60);
+    assert_eq!(timetable.entries[1].stop, "Markt, Nord");
+    assert_eq!(timetable.entries[1].departure, 9 * 3600 + 45 * 60 + 30);
+
+    let err = Timetable::from_csv_str("Hauptbahnhof,09:40\nMarkt,25:00\n").unwrap_err();
+    assert_eq!(err.line, 2);
+    let err = Timetable::from_csv_str("Hauptbahnhof\n").unwrap_err();
+    assert_eq!(err.line, 1);
+}
+
+#[test]
+fn test_dwell_and_delay() {
+    let timetable =
+        Timetable::from_csv_str("Hauptbahnhof,09:40\nMarkt,09:45\nRathaus,09:50\n").unwrap();
+    let mut tracker = PunctualityTracker::new().timetable(timetable);
+
+    // first stop: doors open 20 s, departure one minute late
+    tracker.update(&snapshot(false, false), &world("09:39:00"));
+    tracker.update(&snapshot(true, false), &world("09:40:30"));
+    tracker.update(&snapshot(true, true), &world("09:40:35"));
+    tracker.update(&snapshot(true, false), &world("09:40:55"));
+    tracker.update(&snapshot(false, false), &world("09:41:00"));
+
+    // passing a stop without opening doors is not a served stop
+    tracker.update(&snapshot(true, false), &world("09:43:00"));
+    tracker.update(&snapshot(false, false), &world("09:43:05"));
+
+    // second stop: departure 30 s early
+    tracker.update(&snapshot(true, true), &world("09:44:00"));
+    tracker.update(&snapshot(true, false), &world("09:44:20"));
+    tracker.update(&snapshot(false, false), &world("09:44:30"));
+
+    // third stop: still at the stop
+    tracker.update(&snapshot(true, true), &world("09:52:00"));
+
+    let visits = tracker.visits();
+    assert_eq!(visits.len(), 3);
+
+    assert_eq!(visits[0].stop.as_deref(), Some("Hauptbahnhof"));
+    assert_eq!(visits[0].arrival, "09:40:30");
+    assert_eq!(visits[0].departure.as_deref(), Some("09:41:00"));
+    assert_eq!(visits[0].dwell_s, 20);
+    assert_eq!(visits[0].delay_s, Some(60));
+
+    assert_eq!(visits[1].stop.as_deref(), Some("Markt"));
+    assert_eq!(visits[1].dwell_s, 20);
+    assert_eq!(visits[1].delay_s, Some(-30));
+
+    assert_eq!(visits[2].stop.as_deref(), Some("Rathaus"));
+    assert_eq!(visits[2].departure, None);
+    assert_eq!(visits[2].delay_s, Some(120));
+}
+
+#[test]
+fn test_delay_across_midnight() {
+    let timetable = Timetable::from_csv_str("Depot,23:59\n").unwrap();
+    let mut tracker = PunctualityTracker::new().timetable(timetable);
+
+    tracker.update(&snapshot(true, true), &world("23:58:00"));
+    tracker.update(
+        &snapshot(false, false),
+        &ApiWorldType {
+            date_time: "2026-01-02T00:01:00".to_string(),
+            ..Default::default()
+        },
+    );
+
+    let visits = tracker.visits();
+    assert_eq!(visits[0].delay_s, Some(120));
+    assert_eq!(visits[0].dwell_s, 180);
+}
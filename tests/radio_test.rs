@@ -0,0 +1,46 @@
+mod common;
+
+use common::load_vehicle;
+use the_bus_telemetry::radio::{RadioKey, RadioState, dial_keys};
+
+#[test]
+fn test_radio_state_from_vehicle() {
+    let radio = RadioState::from_vehicle(&load_vehicle("man_lionscity.json"));
+
+    assert!(!radio.playing);
+    assert_eq!(radio.volume, 0.0);
+    assert!(radio.has_keypad());
+    assert_eq!(radio.keys.len(), 26);
+    for d in 0..=9 {
+        assert!(radio.keys.contains(&RadioKey::Digit(d)));
+    }
+    assert!(radio.keys.contains(&RadioKey::Send));
+    assert!(radio.keys.contains(&RadioKey::VolumePlus));
+    assert!(radio.keys.contains(&RadioKey::Emergency));
+}
+
+#[test]
+fn test_radio_key_names_roundtrip() {
+    let vehicle = load_vehicle("man_lionscity.json");
+    for button in vehicle
+        .buttons
+        .iter()
+        .filter(|b| b.name.starts_with("Radio_"))
+    {
+        let key = RadioKey::from_button_name(&button.name).expect(&button.name);
+        assert_eq!(key.button_name(), button.name);
+    }
+
+    assert_eq!(RadioKey::from_button_name("Radio_10"), None);
+    assert_eq!(RadioKey::from_button_name("Wiper"), None);
+}
+
+#[test]
+fn test_dial_keys() {
+    assert_eq!(
+        dial_keys("42"),
+        Some(vec![RadioKey::Digit(4), RadioKey::Digit(2), RadioKey::Send])
+    );
+    assert_eq!(dial_keys(""), None);
+    assert_eq!(dial_keys("4a"), None);
+}
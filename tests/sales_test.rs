@@ -0,0 +1,117 @@
+mod common;
+
+use common::load_vehicle;
+use the_bus_telemetry::ApiVehicleType;
+use the_bus_telemetry::sales::{COIN_BUTTONS, RevenueLedger, change_events};
+
+fn snapshot(at_stop: bool, sales: serde_json::Value) -> ApiVehicleType {
+    serde_json::from_value(serde_json::json!({
+        "ActorName": "Bus",
+        "VehicleModel": "Test",
+        "IgnitionEnabled": "true",
+        "EngineStarted": "true",
+        "WarningLights": "false",
+        "PassengerDoorsOpen": "false",
+        "FixingBrake": "false",
+        "Speed": 0.0,
+        "AllowedSpeed": 50.0,
+        "DisplayFuel": 1.0,
+        "IndicatorState": 0,
+        "IsAtStop": at_stop.to_string(),
+        "AllLamps": {
+            "LightHeadlight": 0.0,
+            "Light Parking": 0.0,
+            "Light MAIN": 0.0,
+            "LightTraveling": 0.0,
+            "Door Button 1": 0.0,
+            "Door Button 2": 0.0,
+            "LED StopRequest": 0.0
+        },
+        "BusLogic": { "Sales": sales }
+    }))
+    .expect("invalid vehicle")
+}
+
+#[test]
+fn test_bus_logic_parsed() {
+    let vehicle = load_vehicle("man_lionscity.json");
+    assert!(vehicle.bus_logic.sales.is_empty());
+    assert!(vehicle.bus_logic.typed_sales().is_empty());
+}
+
+#[test]
+fn test_coin_buttons_exist_in_payload() {
+    let vehicle = load_vehicle("man_lionscity.json");
+
+    for (name, _, event) in COIN_BUTTONS {
+        let button = vehicle.get_button(name).expect(name);
+        assert_eq!(button.actions, vec![event.to_string()]);
+    }
+}
+
+#[test]
+fn test_change_events() {
+    assert_eq!(change_events(0), Vec::<&str>::new());
+    assert_eq!(change_events(175), vec!["Coins100", "Coins60", "Coins15"]);
+    assert_eq!(
+        change_events(1640),
+        vec!["Coins800", "Coins800", "Coins30", "Coins10"]
+    );
+    // rounded down to 5 cents
+    assert_eq!(change_events(7), vec!["Coins5"]);
+}
+
+#[test]
+fn test_revenue_ledger() {
+    let mut ledger = RevenueLedger::new();
+    let sale = |ticket: &str, price: f32| serde_json::json!({ "Ticket": ticket, "Price": price });
+
+    assert_eq!(ledger.update(&snapshot(true, serde_json::json!({}))), 0);
+    let sales = serde_json::json!({ "1": sale("Single", 2.9), "2": sale("Child", 1.5) });
+    assert_eq!(ledger.update(&snapshot(true, sales.clone())), 2);
+    // the same sales again are not recorded twice
+    assert_eq!(ledger.update(&snapshot(false, sales)), 0);
+
+    let sales = serde_json::json!({
+        "1": sale("Single", 2.9),
+        "2": sale("Child", 1.5),
+        "3": sale("Day", 7.0),
+        "4": "unexpected"
+    });
+    assert_eq!(ledger.update(&snapshot(true, sales)), 2);
+
+    assert_eq!(ledger.entries().len(), 4);
+    assert_eq!(ledger.entries()[2].sale.as_ref().unwrap().ticket, "Day");
+    assert!((ledger.total() - 11.4).abs() < 1e-5);
+
+    // sales that don't match the schema are kept with their parse error
+    let unparsed: Vec<_> = ledger.unparsed().collect();
+    assert_eq!(unparsed.len(), 1);
+    assert_eq!(unparsed[0].id, "4");
+    assert_eq!(unparsed[0].value, serde_json::json!("unexpected"));
+
+    let per_stop = ledger.per_stop();
+    assert_eq!(per_stop.len(), 2);
+    assert_eq!(per_stop[0].stop, 0);
+    assert_eq!(per_stop[0].tickets, 2);
+    assert_eq!(per_stop[1].stop, 1);
+    assert_eq!(per_stop[1].tickets, 2);
+    assert!((per_stop[1].revenue - 7.0).abs() < 1e-5);
+}
+
+#[test]
+fn test_typed_sales_keep_parse_errors() {
+    let vehicle = snapshot(
+        false,
+        serde_json::json!({
+            "1": { "Ticket": "Single", "Price": 2.9 },
+            "2": { "Name": "Single", "Cost": 2.9 }
+        }),
+    );
+
+    let sales = vehicle.bus_logic.typed_sales();
+    assert_eq!(sales.len(), 2);
+    assert_eq!(sales[0].1.as_ref().unwrap().ticket, "Single");
+    let error = sales[1].1.as_ref().unwrap_err().to_string();
+    assert!(error.contains("Ticket"), "{}", error);
+}
@@ -0,0 +1,113 @@
+use std::time::Duration;
+use the_bus_telemetry::score::DrivingScore;
+use the_bus_telemetry::{ApiRotation, ApiVehicleType};
+
+fn snapshot(speed: f32) -> ApiVehicleType {
+    ApiVehicleType {
+        speed,
+        allowed_speed: 50.0,
+        passenger_doors_open: "false".to_string(),
+        fixing_brake: "false".to_string(),
+        ..Default::default()
+    }
+}
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn test_clean_trip_scores_full() {
+    let mut score = DrivingScore::new();
+    for s in 0..60 {
+        score.update(Duration::from_secs(s), &snapshot(40.0));
+    }
+
+    let report = score.report();
+    assert_eq!(report.score, 100.0);
+    assert_eq!(report.harsh_braking, 0);
+    assert_eq!(report.speeding, 0);
+}
+
+#[test]
+fn test_harsh_braking_and_acceleration() {
+    let mut score = DrivingScore::new();
+
+    // 50 -> 20 km/h in 2 s is about 4.2 m/s², counted once
+    score.update(ms(0), &snapshot(50.0));
+    score.update(ms(1000), &snapshot(35.0));
+    score.update(ms(2000), &snapshot(20.0));
+    score.update(ms(3000), &snapshot(20.0));
+
+    // full brake input counts as well
+    let mut full_brake = snapshot(20.0);
+    full_brake.brake = 1.0;
+    score.update(ms(4000), &full_brake);
+    score.update(ms(5000), &snapshot(19.0));
+
+    // 0 -> 20 km/h in 2 s is about 2.8 m/s²
+    score.update(ms(10000), &snapshot(0.0));
+    score.update(ms(11000), &snapshot(10.0));
+    score.update(ms(12000), &snapshot(20.0));
+
+    let report = score.report();
+    assert_eq!(report.harsh_braking, 2);
+    assert_eq!(report.harsh_acceleration, 1);
+    assert_eq!(report.score, 100.0 - 10.0 - 3.0);
+}
+
+#[test]
+fn test_speeding_doors_and_fixing_brake() {
+    let mut score = DrivingScore::new();
+
+    for s in 0..=10 {
+        score.update(Duration::from_secs(s), &snapshot(60.0));
+    }
+    // within tolerance
+    score.update(Duration::from_secs(11), &snapshot(54.0));
+
+    let mut doors_open = snapshot(5.0);
+    doors_open.passenger_doors_open = "true".to_string();
+    score.update(Duration::from_secs(30), &doors_open);
+
+    let mut fixing_brake = snapshot(5.0);
+    fixing_brake.fixing_brake = "true".to_string();
+    score.update(Duration::from_secs(40), &fixing_brake);
+
+    let report = score.report();
+    assert_eq!(report.speeding, 1);
+    assert_eq!(report.speeding_time_s, 10.0);
+    assert_eq!(report.doors_open_driving, 1);
+    assert_eq!(report.fixing_brake_departures, 1);
+}
+
+#[test]
+fn test_turns_without_indicator() {
+    let mut score = DrivingScore::new();
+    let turn = |score: &mut DrivingScore, start: u64, indicator: i8| {
+        for i in 0..=4 {
+            let mut v = snapshot(20.0);
+            v.steering = if i == 4 { 0.0 } else { 0.6 };
+            v.indicator_state = indicator;
+            // yaw wraps around from 170 to -170 degrees
+            v.rotation = ApiRotation {
+                yaw: if i < 2 {
+                    150.0 + 20.0 * i as f32
+                } else {
+                    -190.0 + 20.0 * i as f32
+                },
+                ..Default::default()
+            };
+            score.update(Duration::from_secs(start + i), &v);
+        }
+    };
+
+    turn(&mut score, 0, 1);
+    turn(&mut score, 100, 0);
+    // right turn while indicating left
+    turn(&mut score, 200, -1);
+
+    let report = score.report();
+    assert_eq!(report.turns, 3);
+    assert_eq!(report.turns_without_indicator, 2);
+}
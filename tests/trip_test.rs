@@ -0,0 +1,76 @@
+use std::time::Duration;
+use the_bus_telemetry::ApiVehicleType;
+use the_bus_telemetry::trip::TripComputer;
+
+fn snapshot(speed: f32, allowed_speed: f32, doors_open: bool) -> ApiVehicleType {
+    ApiVehicleType {
+        speed,
+        allowed_speed,
+        passenger_doors_open: doors_open.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_trip_distance_and_times() {
+    let mut trip = TripComputer::new();
+
+    // 60 s at 36 km/h, then 30 s at 60 km/h with an allowed speed of 50 km/h
+    for s in 0..=60 {
+        trip.update(Duration::from_secs(s), &snapshot(36.0, 50.0, false));
+    }
+    for s in 61..=90 {
+        trip.update(Duration::from_secs(s), &snapshot(60.0, 50.0, false));
+    }
+
+    let report = trip.report();
+    let expected_km = (36.0 * 60.0 + 48.0 + 60.0 * 29.0) / 3600.0;
+    assert!((report.distance_km - expected_km).abs() < 1e-9);
+    assert_eq!(report.driving_time_s, 90.0);
+    assert_eq!(report.standing_time_s, 0.0);
+    assert_eq!(report.max_speed_kmh, 60.0);
+    assert_eq!(report.speeding_time_s, 30.0);
+    assert!((report.average_speed_kmh - expected_km / (90.0 / 3600.0)).abs() < 1e-9);
+}
+
+#[test]
+fn test_trip_counts_stops_with_open_doors() {
+    let mut trip = TripComputer::new();
+    let mut t = 0;
+    let mut feed = |trip: &mut TripComputer, speed: f32, doors: bool| {
+        trip.update(Duration::from_secs(t), &snapshot(speed, 50.0, doors));
+        t += 1;
+    };
+
+    feed(&mut trip, 30.0, false);
+    // standing at a traffic light: not a stop
+    feed(&mut trip, 0.0, false);
+    feed(&mut trip, 0.0, false);
+    feed(&mut trip, 30.0, false);
+    // bus stop: doors open and close once
+    feed(&mut trip, 0.0, false);
+    feed(&mut trip, 0.0, true);
+    feed(&mut trip, 0.0, false);
+    feed(&mut trip, 0.0, true);
+    assert!(trip.is_standing());
+    feed(&mut trip, 20.0, false);
+
+    let report = trip.report();
+    assert_eq!(report.stops, 1);
+    assert_eq!(report.standing_time_s, 6.0);
+}
+
+#[test]
+fn test_trip_ignores_gaps() {
+    let mut trip = TripComputer::new();
+    trip.update(Duration::from_secs(0), &snapshot(50.0, 0.0, false));
+    trip.update(Duration::from_secs(600), &snapshot(50.0, 0.0, false));
+
+    let report = trip.report();
+    assert_eq!(report.distance_km, 0.0);
+    assert_eq!(report.driving_time_s, 0.0);
+    assert_eq!(report.speeding_time_s, 0.0);
+
+    trip.reset();
+    assert_eq!(trip.report().max_speed_kmh, 0.0);
+}
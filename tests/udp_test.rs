@@ -0,0 +1,75 @@
+mod common;
+
+use common::load_vehicle;
+use std::net::UdpSocket;
+use std::time::Duration;
+use the_bus_telemetry::api2vehicle::get_vehicle_state_from_api;
+use the_bus_telemetry::udp::{
+    FLAG_ENGINE, FLAG_IGNITION, PACKET_LEN, PacketError, TelemetryPacket, UdpBroadcaster,
+};
+
+#[test]
+fn test_packet_roundtrip() {
+    let vehicle = load_vehicle("man_lionscity.json");
+    let state = get_vehicle_state_from_api(vehicle.clone());
+    let packet = TelemetryPacket::new(7, &state, &vehicle);
+
+    assert_eq!(packet.flag(FLAG_IGNITION), state.ignition);
+    assert_eq!(packet.flag(FLAG_ENGINE), state.engine);
+    assert_eq!(packet.brake, vehicle.brake);
+    assert_eq!(packet.yaw, vehicle.rotation.yaw);
+
+    let bytes = packet.encode();
+    assert_eq!(bytes.len(), PACKET_LEN);
+    assert_eq!(&bytes[0..3], b"TB\x01");
+    assert_eq!(TelemetryPacket::decode(&bytes), Ok(packet.clone()));
+
+    // newer versions append fields
+    let mut longer = bytes.to_vec();
+    longer[2] = 2;
+    longer.extend_from_slice(&[0, 0, 0, 0]);
+    assert_eq!(TelemetryPacket::decode(&longer), Ok(packet));
+}
+
+#[test]
+fn test_packet_decode_errors() {
+    let bytes = TelemetryPacket::default().encode();
+
+    assert_eq!(
+        TelemetryPacket::decode(&bytes[..10]),
+        Err(PacketError::TooShort(10))
+    );
+    assert_eq!(
+        TelemetryPacket::decode(b"XX\x01"),
+        Err(PacketError::BadMagic)
+    );
+
+    let mut bad_version = bytes;
+    bad_version[2] = 0;
+    assert_eq!(
+        TelemetryPacket::decode(&bad_version),
+        Err(PacketError::UnsupportedVersion(0))
+    );
+}
+
+#[test]
+fn test_broadcaster_sends_numbered_packets() {
+    let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+    receiver
+        .set_read_timeout(Some(Duration::from_secs(2)))
+        .unwrap();
+
+    let vehicle = load_vehicle("vdl_citea.json");
+    let state = get_vehicle_state_from_api(vehicle.clone());
+    let mut broadcaster = UdpBroadcaster::new(receiver.local_addr().unwrap()).unwrap();
+    broadcaster.send(&state, &vehicle).unwrap();
+    broadcaster.send(&state, &vehicle).unwrap();
+
+    let mut buf = [0u8; 128];
+    for expected in 0..2 {
+        let (n, _) = receiver.recv_from(&mut buf).unwrap();
+        let packet = TelemetryPacket::decode(&buf[..n]).unwrap();
+        assert_eq!(packet.sequence, expected);
+        assert_eq!(packet.speed as u32, state.speed);
+    }
+}
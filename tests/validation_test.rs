@@ -0,0 +1,74 @@
+mod common;
+
+use common::load_json;
+use serde_json::json;
+use the_bus_telemetry::validation::{validate_vehicle_payload, validate_world_payload};
+
+#[test]
+fn test_validate_vehicle_payloads() {
+    // MAN Lion's City has no "Light MAIN", which is defaulted
+    let report = validate_vehicle_payload(&load_json("man_lionscity.json"));
+    assert_eq!(report.vehicle_model, "Lions City");
+    assert!(report.error.is_none());
+    assert!(report.missing_fields.is_empty());
+    assert!(
+        report
+            .defaulted_fields
+            .contains(&"AllLamps.Light MAIN".to_string())
+    );
+    assert!(report.unknown_fields.contains(&"Mass".to_string()));
+    assert!(report.unmapped_lamps.contains(&"LED ABS".to_string()));
+    assert!(!report.unmapped_lamps.contains(&"LightParking1".to_string()));
+    assert!(!report.is_clean());
+
+    let report = validate_vehicle_payload(&load_json("BP_Mercedes_eCitaro_12m_2Door_C.json"));
+    assert!(report.error.is_none());
+    assert!(report.missing_fields.is_empty());
+}
+
+#[test]
+fn test_validate_vehicle_payload_renamed_field() {
+    let mut data = load_json("vdl_citea.json");
+    let object = data.as_object_mut().unwrap();
+    let speed = object.remove("Speed").unwrap();
+    object.insert("VehicleSpeed".to_string(), speed);
+    object.remove("Buttons");
+
+    let report = validate_vehicle_payload(&data);
+    assert_eq!(report.missing_fields, vec!["Speed".to_string()]);
+    assert!(report.defaulted_fields.contains(&"Buttons".to_string()));
+    assert!(report.unknown_fields.contains(&"VehicleSpeed".to_string()));
+    assert!(report.error.is_some());
+}
+
+#[test]
+fn test_validate_vehicle_payload_array_items() {
+    let mut data = load_json("man_lionscity.json");
+    data["Buttons"][0]["Shortcut"] = json!("K");
+    data["Buttons"][1]["Shortcut"] = json!("L");
+    data["Doors"][0].as_object_mut().unwrap().remove("Name");
+    data["Wheels"][0]["Grip"] = json!(0.9);
+
+    let report = validate_vehicle_payload(&data);
+    assert_eq!(
+        report
+            .unknown_fields
+            .iter()
+            .filter(|f| f.as_str() == "Buttons[].Shortcut")
+            .count(),
+        1
+    );
+    assert!(report.unknown_fields.contains(&"Wheels[].Grip".to_string()));
+    assert!(report.missing_fields.contains(&"Doors[].Name".to_string()));
+    assert!(report.error.is_some());
+}
+
+#[test]
+fn test_validate_world_payload() {
+    let report = validate_world_payload(&load_json("world.json"));
+    assert!(report.is_clean());
+
+    let report = validate_world_payload(&json!({ "LevelName": "Castrop" }));
+    assert!(report.missing_fields.contains(&"DateTime".to_string()));
+    assert!(report.error.is_some());
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "websocket")]
+
+use serde_json::json;
+use the_bus_telemetry::websocket::{ClientMessage, json_diff, update_message};
+
+#[test]
+fn test_json_diff() {
+    let old = json!({
+        "vehicle": { "Speed": 10.0, "AllLamps": { "LightBrake": 1.0, "LED ABS": 0.0 } },
+        "world": { "LevelName": "Castrop" }
+    });
+    let new = json!({
+        "vehicle": { "Speed": 12.5, "AllLamps": { "LightBrake": 1.0 } },
+        "world": { "LevelName": "Castrop" }
+    });
+
+    assert_eq!(json_diff(&old, &old), None);
+    assert_eq!(
+        json_diff(&old, &new),
+        Some(json!({ "vehicle": { "Speed": 12.5, "AllLamps": { "LED ABS": null } } }))
+    );
+
+    // vehicle left
+    let gone = json!({ "vehicle": null, "world": { "LevelName": "Castrop" } });
+    assert_eq!(json_diff(&new, &gone), Some(json!({ "vehicle": null })));
+}
+
+#[test]
+fn test_update_message() {
+    let old = json!({
+        "vehicle": { "ActorName": "Bus_1", "Speed": 10.0 },
+        "world": { "LevelName": "Castrop" }
+    });
+    let faster = json!({
+        "vehicle": { "ActorName": "Bus_1", "Speed": 12.5 },
+        "world": { "LevelName": "Castrop" }
+    });
+    assert_eq!(update_message(&old, &old), None);
+    assert_eq!(
+        update_message(&old, &faster),
+        Some(json!({ "type": "diff", "data": { "vehicle": { "Speed": 12.5 } } }))
+    );
+
+    // another vehicle, no vehicle and real null values are sent as snapshots
+    let other = json!({
+        "vehicle": { "ActorName": "Bus_2", "Speed": 10.0 },
+        "world": { "LevelName": "Castrop" }
+    });
+    let gone = json!({ "vehicle": null, "world": { "LevelName": "Castrop" } });
+    let null_field = json!({
+        "vehicle": { "ActorName": "Bus_1", "Speed": null },
+        "world": { "LevelName": "Castrop" }
+    });
+    for new in [other, gone, null_field] {
+        assert_eq!(
+            update_message(&old, &new),
+            Some(json!({ "type": "snapshot", "data": new }))
+        );
+    }
+}
+
+#[test]
+fn test_client_message() {
+    let msg: ClientMessage =
+        serde_json::from_str(r#"{"type":"cmd","cmd":"sendeventpress?event=Horn"}"#).unwrap();
+    assert_eq!(
+        msg,
+        ClientMessage::Cmd {
+            cmd: "sendeventpress?event=Horn".to_string()
+        }
+    );
+    assert!(serde_json::from_str::<ClientMessage>(r#"{"type":"dance"}"#).is_err());
+}
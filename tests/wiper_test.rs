@@ -0,0 +1,87 @@
+mod common;
+
+use common::{load_vehicle, load_world};
+use the_bus_telemetry::ApiButton;
+use the_bus_telemetry::wiper::{AutoWiper, WiperPosition, WiperState, wiper_events};
+
+#[test]
+fn test_wiper_state_from_payload() {
+    for name in ["man_lionscity.json", "BP_Solaris_Urbino_18m_4D_C.json"] {
+        let wiper = WiperState::from_vehicle(&load_vehicle(name));
+        assert_eq!(wiper.position, Some(WiperPosition::Off), "{}", name);
+        assert_eq!(wiper.level, 0);
+        assert!(!wiper.intermittent);
+        assert_eq!(wiper.washer, None);
+    }
+
+    let mut v = load_vehicle("man_lionscity.json");
+    let button = v.buttons.iter_mut().find(|b| b.name == "Wiper").unwrap();
+    button.state = "Interval".to_string();
+    v.buttons.push(ApiButton {
+        name: "Washer".to_string(),
+        state: "true".to_string(),
+        ..Default::default()
+    });
+    let wiper = WiperState::from_vehicle(&v);
+    assert_eq!(wiper.position, Some(WiperPosition::Interval));
+    assert!(wiper.intermittent);
+    assert_eq!(wiper.washer, Some(true));
+}
+
+#[test]
+fn test_washer_matched_by_exact_name_or_action() {
+    let mut v = load_vehicle("man_lionscity.json");
+    // lamps and other buttons mentioning the washer are not the washer
+    v.buttons.push(ApiButton {
+        name: "ButtonLight Washer".to_string(),
+        state: "true".to_string(),
+        ..Default::default()
+    });
+    assert_eq!(WiperState::from_vehicle(&v).washer, None);
+
+    v.buttons.push(ApiButton {
+        name: "Windscreen".to_string(),
+        state: "Primary".to_string(),
+        actions: vec!["windowwasher".to_string()],
+        ..Default::default()
+    });
+    assert_eq!(WiperState::from_vehicle(&v).washer, Some(false));
+}
+
+#[test]
+fn test_wiper_events() {
+    assert_eq!(
+        wiper_events(WiperPosition::Off, WiperPosition::On),
+        vec!["WiperUp", "WiperUp"]
+    );
+    assert_eq!(
+        wiper_events(WiperPosition::Fast, WiperPosition::Interval),
+        vec!["WiperDown", "WiperDown"]
+    );
+    assert!(wiper_events(WiperPosition::On, WiperPosition::On).is_empty());
+    assert_eq!(WiperPosition::from_state("fast"), Some(WiperPosition::Fast));
+}
+
+#[test]
+fn test_auto_wiper_hysteresis() {
+    let mut auto = AutoWiper::new();
+    assert_eq!(auto.update(0.0), WiperPosition::Off);
+    assert_eq!(auto.update(0.06), WiperPosition::Off);
+    assert_eq!(auto.update(0.1), WiperPosition::Interval);
+    // Hovering around the threshold keeps the position.
+    assert_eq!(auto.update(0.04), WiperPosition::Interval);
+    assert_eq!(auto.update(0.01), WiperPosition::Off);
+    assert_eq!(auto.update(0.9), WiperPosition::Fast);
+    assert_eq!(auto.update(0.5), WiperPosition::On);
+}
+
+#[test]
+fn test_auto_wiper_events() {
+    let v = load_vehicle("scania_citywide.json");
+    let mut world = load_world();
+    let mut auto = AutoWiper::new();
+
+    assert!(auto.events(&v, &world).is_empty());
+    world.rain_intensity = 0.5;
+    assert_eq!(auto.events(&v, &world), vec!["WiperUp", "WiperUp"]);
+}